@@ -1,7 +1,19 @@
-use clap::Parser as ClapParser;
-use financial_parser::format::common::prepare_transactions;
-use financial_parser::format::Format;
-use std::path::PathBuf;
+use clap::{Parser as ClapParser, ValueEnum};
+use financial_parser::compression::decompress_to_vec;
+use financial_parser::diagnostics::render;
+use financial_parser::errors::ParseError;
+use financial_parser::format::registry::find_input_format;
+use financial_parser::format::{ReadOptions, TransactionFormat};
+use financial_parser::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use financial_parser::model::transaction::Transaction;
+use financial_parser::parser::Parser;
+use financial_parser::reconciliation::{reconcile_with_policy, ComparePolicy, Reconciliation, TransactionField};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::Arc;
 
 #[derive(Debug, ClapParser)]
 #[command(name = "cli-comparer")]
@@ -13,55 +25,238 @@ struct Args {
     #[arg(
         short = 'f',
         long = "format1",
-        default_value = "csv",
-        help = "Input format for file1: csv, text, binary"
+        default_value = "auto",
+        value_parser = find_input_format,
+        help = "Input format for file1: auto, csv, text, binary, json, jsonl or any registered format"
     )]
-    format1: Format,
+    format1: Arc<dyn TransactionFormat>,
     #[arg(short = '2', long = "file2", required = true)]
     file2: PathBuf,
     #[arg(
         short = 'F',
         long = "format2",
-        default_value = "csv",
-        help = "Input format for file2: csv, text, binary"
+        default_value = "auto",
+        value_parser = find_input_format,
+        help = "Input format for file2: auto, csv, text, binary, json, jsonl or any registered format"
     )]
-    format2: Format,
+    format2: Arc<dyn TransactionFormat>,
+    #[arg(
+        long = "default-currency",
+        value_parser = CurrencyCode::from_str,
+        help = "Currency of amounts in inputs, which do not specify it (USD by default)"
+    )]
+    default_currency: Option<CurrencyCode>,
+    #[arg(
+        long = "strict-descriptions",
+        help = "Reject text format descriptions with unknown escapes or unescaped quotes"
+    )]
+    strict_descriptions: bool,
+    #[arg(
+        short = 'o',
+        long = "output",
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "Format of the comparison report"
+    )]
+    output: OutputFormat,
+    #[arg(
+        long = "ignore",
+        value_enum,
+        help = "Field, which is not compared; can be repeated"
+    )]
+    ignore: Vec<TransactionField>,
+    #[arg(
+        long = "timestamp-tolerance",
+        default_value = "0ms",
+        value_parser = parse_duration_millis,
+        help = "Maximal difference of timestamps, e.g. 500ms, 2s or 1m"
+    )]
+    timestamp_tolerance: u64,
+    #[arg(long = "ignore-description-case", help = "Compare descriptions ignoring letter case")]
+    ignore_description_case: bool,
+    #[arg(
+        long = "ignore-description-whitespace",
+        help = "Compare descriptions ignoring leading, trailing and repeated whitespace"
+    )]
+    ignore_description_whitespace: bool,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// Format of the comparison report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable report
+    Text,
+    /// JSON object with the reconciliation result
+    Json,
+    /// CSV table with a row per difference
+    Csv,
+}
+
+/// Exit code, when the files are identical
+const EXIT_IDENTICAL: u8 = 0;
+/// Exit code, when the files differ
+const EXIT_DIFFERENT: u8 = 1;
+/// Exit code, when the files can not be compared
+const EXIT_ERROR: u8 = 2;
+
+fn main() -> ExitCode {
     let args = Args::parse();
-    let result = prepare_transactions(&args.file1, args.format1, &args.file2, args.format2)?;
-    if result.0 == result.1 {
+    match run(&args) {
+        Ok(true) => ExitCode::from(EXIT_IDENTICAL),
+        Ok(false) => ExitCode::from(EXIT_DIFFERENT),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// Compares the files and prints the report
+///
+/// # Returning value
+///
+/// Returns `Result<bool, Box<dyn std::error::Error>>`:
+/// - `Ok(bool)` — `true` if the files are identical
+/// - `Err(Box<dyn std::error::Error>)` — the files can not be read or the report can not be written
+fn run(args: &Args) -> Result<bool, Box<dyn std::error::Error>> {
+    let read_options = ReadOptions {
+        default_currency: args.default_currency.unwrap_or(DEFAULT_CURRENCY),
+        strict_descriptions: args.strict_descriptions,
+    };
+    let left = parse_file(&args.file1, &args.format1, &read_options)?;
+    let right = parse_file(&args.file2, &args.format2, &read_options)?;
+    let policy = ComparePolicy {
+        ignored_fields: args.ignore.clone(),
+        timestamp_tolerance: args.timestamp_tolerance,
+        ignore_description_case: args.ignore_description_case,
+        ignore_description_whitespace: args.ignore_description_whitespace,
+    };
+    let reconciliation = reconcile_with_policy(&left, &right, &policy);
+    match args.output {
+        OutputFormat::Text => print_text(&reconciliation, &args.file1, &args.file2),
+        OutputFormat::Json => print_json(&reconciliation, &args.file1, &args.file2)?,
+        OutputFormat::Csv => print_csv(&reconciliation)?,
+    }
+    Ok(reconciliation.is_identical())
+}
+
+/// Parses duration with `ms`, `s` or `m` unit into milliseconds, a bare number means milliseconds
+fn parse_duration_millis(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (number, multiplier) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 1)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1000)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 60_000)
+    } else {
+        (value, 1)
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(multiplier))
+        .ok_or_else(|| format!("Invalid duration '{}', expected e.g. 500ms, 2s or 1m", value))
+}
+
+/// Prints the human-readable report
+fn print_text(reconciliation: &Reconciliation, file1: &Path, file2: &Path) {
+    if reconciliation.is_identical() {
         println!(
             "The transaction records in '{}' and '{}' are identical.",
-            args.file1.display(),
-            args.file2.display()
+            file1.display(),
+            file2.display()
         );
     } else {
-        let mismatch = result
-            .0
-            .iter()
-            .zip(result.1.iter())
-            .position(|(a, b)| a != b);
-        if let Some(index) = mismatch {
-            eprintln!(
-                "Mismatch at transaction index {}: {:?} vs {:?}",
-                index,
-                result.0.get(index),
-                result.1.get(index)
-            );
-        } else if result.0.len() != result.1.len() {
-            eprintln!(
-                "Transaction lists have different lengths: {} vs {}",
-                result.0.len(),
-                result.1.len()
-            );
-        }
+        print_reconciliation(reconciliation, file1, file2);
         eprintln!(
             "The transaction records in '{}' and '{}' do not match.",
-            args.file1.display(),
-            args.file2.display()
+            file1.display(),
+            file2.display()
         );
     }
+}
+
+/// Prints the report as a JSON object
+fn print_json(reconciliation: &Reconciliation, file1: &Path, file2: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let report = serde_json::json!({
+        "file1": file1.display().to_string(),
+        "file2": file2.display().to_string(),
+        "identical": reconciliation.is_identical(),
+        "matched": reconciliation.matched,
+        "left_only": reconciliation.left_only,
+        "right_only": reconciliation.right_only,
+        "different": reconciliation.different,
+    });
+    serde_json::to_writer_pretty(std::io::stdout().lock(), &report)?;
+    println!();
+    Ok(())
+}
+
+/// Prints the report as a CSV table
+///
+/// Every transaction, found only in one of the files, takes a row with the whole transaction
+/// in its column, every differing field takes a row with both values.
+fn print_csv(reconciliation: &Reconciliation) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout().lock());
+    writer.write_record(["KIND", "TX_ID", "FIELD", "LEFT", "RIGHT"])?;
+    for transaction in &reconciliation.left_only {
+        writer.write_record(["LEFT_ONLY", &transaction.tx_id.to_string(), "", &transaction.to_string(), ""])?;
+    }
+    for transaction in &reconciliation.right_only {
+        writer.write_record(["RIGHT_ONLY", &transaction.tx_id.to_string(), "", "", &transaction.to_string()])?;
+    }
+    for diff in &reconciliation.different {
+        for field in &diff.fields {
+            writer.write_record(["DIFFERENT", &diff.left.tx_id.to_string(), field.field, &field.left, &field.right])?;
+        }
+    }
+    writer.flush()?;
     Ok(())
 }
+
+/// Prints transactions, found only in one of the files, and per-field differences
+fn print_reconciliation(reconciliation: &Reconciliation, file1: &Path, file2: &Path) {
+    for (transactions, path) in [(&reconciliation.left_only, file1), (&reconciliation.right_only, file2)] {
+        if !transactions.is_empty() {
+            println!("Only in '{}': {}", path.display(), transactions.len());
+            for transaction in transactions {
+                println!("  {}", transaction);
+            }
+        }
+    }
+    if !reconciliation.different.is_empty() {
+        println!("Different: {}", reconciliation.different.len());
+        for diff in &reconciliation.different {
+            println!("  tx_id {}:", diff.left.tx_id);
+            for field in &diff.fields {
+                println!("    {}: {:?} vs {:?}", field.field, field.left, field.right);
+            }
+        }
+    }
+    println!("Matched: {}", reconciliation.matched);
+}
+
+/// Parses the file, printing parse errors against the file content
+fn parse_file(
+    path: &Path,
+    format: &Arc<dyn TransactionFormat>,
+    read_options: &ReadOptions,
+) -> Result<Vec<Transaction>, Box<dyn std::error::Error>> {
+    let file = File::open(path)
+        .map_err(|e| format!("File can not be opened {}: {}", path.display(), e))?;
+    Parser::iter_with_options(BufReader::new(file), format, read_options)
+        .collect::<Result<_, _>>()
+        .map_err(|e| report_parse_error(&e, path))
+}
+
+/// Prints the parse error against the decompressed input file content and exits
+fn report_parse_error(error: &ParseError, path: &Path) -> ! {
+    let source = File::open(path)
+        .ok()
+        .and_then(|file| decompress_to_vec(file).ok())
+        .unwrap_or_default();
+    eprint!("{}", render(error, &path.display().to_string(), &source));
+    std::process::exit(EXIT_ERROR.into());
+}
@@ -2,11 +2,14 @@
 mod tests {
     use financial_parser::format::common::prepare_transactions;
     use financial_parser::format::Format;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
     use financial_parser::model::transaction::Transaction;
     use financial_parser::model::transaction_status::TransactionStatus;
     use financial_parser::model::transaction_type::TransactionType;
     use financial_parser::parser::Parser;
     use std::io::Write;
+    use std::process::Command;
     use tempfile::NamedTempFile;
     #[test]
     fn test_compare_files() -> Result<(), Box<dyn std::error::Error>> {
@@ -16,7 +19,7 @@ mod tests {
                 tx_type: TransactionType::Deposit,
                 from_user_id: 0,
                 to_user_id: 501,
-                amount: 50000,
+                amount: Money::new(50000, CurrencyCode::USD),
                 timestamp: 1672531200000,
                 status: TransactionStatus::Success,
                 description: "Initial deposit".to_string(),
@@ -37,4 +40,61 @@ mod tests {
         assert_eq!(result.0, result.1);
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Runs cli-comparer with the given arguments
+    fn run_comparer(args: &[&str]) -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--bin", "cli-comparer", "--"])
+            .args(args)
+            .output()?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_compare_output_and_exit_codes() -> Result<(), Box<dyn std::error::Error>> {
+        let transaction = Transaction {
+            tx_id: 1001,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 501,
+            amount: Money::new(50000, CurrencyCode::USD),
+            timestamp: 1672531200000,
+            status: TransactionStatus::Success,
+            description: "Initial deposit".to_string(),
+        };
+        let mut changed = transaction.clone();
+        changed.amount = Money::new(40000, CurrencyCode::USD);
+        let mut file1 = NamedTempFile::new()?;
+        Parser::write(std::slice::from_ref(&transaction), file1.as_file_mut(), Format::Csv)?;
+        file1.flush()?;
+        let mut file2 = NamedTempFile::new()?;
+        Parser::write(&[changed], file2.as_file_mut(), Format::Json)?;
+        file2.flush()?;
+        let path1 = file1.path().to_str().unwrap();
+        let path2 = file2.path().to_str().unwrap();
+
+        let output = run_comparer(&["--file1", path1, "--file2", path1])?;
+        assert_eq!(output.status.code(), Some(0));
+
+        let output = run_comparer(&["--file1", path1, "--file2", path2, "--output", "json"])?;
+        assert_eq!(output.status.code(), Some(1));
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(report["identical"], false);
+        assert_eq!(report["different"][0]["fields"][0]["field"], "AMOUNT");
+        assert_eq!(report["different"][0]["fields"][0]["right"], "400.00 USD");
+
+        let output = run_comparer(&["--file1", path1, "--file2", path2, "--output", "csv"])?;
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8(output.stdout)?,
+            "KIND,TX_ID,FIELD,LEFT,RIGHT\nDIFFERENT,1001,AMOUNT,500.00 USD,400.00 USD\n"
+        );
+
+        let output = run_comparer(&["--file1", path1, "--file2", path2, "--ignore", "amount"])?;
+        assert_eq!(output.status.code(), Some(0));
+
+        let output = run_comparer(&["--file1", path1, "--file2", "missing.csv"])?;
+        assert_eq!(output.status.code(), Some(2));
+        Ok(())
+    }
+}
@@ -1,46 +1,301 @@
 use clap::Parser as ClapParser;
-use financial_parser::format::Format;
+#[cfg(feature = "compression")]
+use financial_parser::compression::{compress, Compression};
+use financial_parser::compression::decompress_to_vec;
+use financial_parser::diagnostics::{render_message, render_with_dialect};
+use financial_parser::errors::ParseError;
+use financial_parser::format::registry::{find_format, find_input_format};
+use financial_parser::format::csv_mapping::CsvMapping;
+use financial_parser::format::yp_bank_csv_format::CsvDialect;
+use financial_parser::format::{Format, ReadOptions, TransactionFormat, WriteOptions};
+use financial_parser::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
 use financial_parser::parser::Parser;
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Read, Write};
-use std::path::PathBuf;
+use std::io::{BufReader, BufWriter, Read, StdoutLock, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tempfile::NamedTempFile;
 
 #[derive(Debug, ClapParser)]
 #[command(name = "cli-converter")]
 #[command(about = "Transaction converter for different formats", long_about = None)]
 #[command(version)]
 struct Args {
-    #[arg(short, long, help = "Input file")]
+    #[arg(short, long, help = "Input file, or - for standard input")]
     input: PathBuf,
     #[arg(
         short = 'f',
         long = "input-format",
-        default_value = "csv",
-        help = "Input format: csv, text, or binary"
+        default_value = "auto",
+        value_parser = find_input_format,
+        help = "Input format: auto, csv, text, binary, json, jsonl or any registered format"
     )]
-    input_format: Format,
-    #[arg(short, long, help = "Output file")]
+    input_format: Arc<dyn TransactionFormat>,
+    #[arg(short, long, help = "Output file, or - for standard output")]
     output: PathBuf,
     #[arg(
         short = 'F',
         long = "output-format",
         default_value = "csv",
-        help = "Output format: csv, text, or binary"
+        value_parser = find_format,
+        help = "Output format: csv, text, binary, json, jsonl or any registered format"
+    )]
+    output_format: Arc<dyn TransactionFormat>,
+    #[arg(
+        long = "default-currency",
+        value_parser = CurrencyCode::from_str,
+        help = "Currency of amounts in inputs, which do not specify it (USD by default)"
+    )]
+    default_currency: Option<CurrencyCode>,
+    #[arg(
+        long = "strict-descriptions",
+        help = "Reject text format descriptions with unknown escapes or unescaped quotes"
+    )]
+    strict_descriptions: bool,
+    #[arg(
+        long = "write-currencies",
+        help = "Write the currency of every amount: CURRENCY column in CSV, version 2 layout in binary; \
+            needed when the input mixes currencies"
+    )]
+    write_currencies: bool,
+    #[arg(long, help = "Mapping of a bank CSV export to transactions, TOML or YAML file; replaces the input format")]
+    mapping: Option<PathBuf>,
+    #[arg(long = "csv-delimiter", value_parser = parse_csv_char, help = "CSV field delimiter (comma by default, \\t for tab)")]
+    csv_delimiter: Option<u8>,
+    #[arg(long = "csv-quote", value_parser = parse_csv_char, help = "CSV quote character (double quote by default)")]
+    csv_quote: Option<u8>,
+    #[arg(
+        long = "csv-header-alias",
+        value_parser = parse_header_alias,
+        help = "Alternative CSV header name of a field: ALIAS=FIELD, e.g. Amount=AMOUNT; can be repeated"
+    )]
+    csv_header_aliases: Vec<(String, String)>,
+    #[arg(long = "csv-no-header", help = "CSV data has no header line")]
+    csv_no_header: bool,
+    #[arg(
+        long = "csv-columns",
+        value_delimiter = ',',
+        help = "Column order of CSV data without a header line, e.g. TX_ID,AMOUNT,...; implies --csv-no-header"
     )]
-    output_format: Format,
+    csv_columns: Option<Vec<String>>,
+    #[cfg(feature = "compression")]
+    #[arg(long, help = "Compress output: gzip or zstd")]
+    compress: Option<Compression>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
-    let file = File::open(&args.input)
-        .map_err(|e| format!("File can not be opened {}: {}", &args.input.display(), e))?;
-    let mut input_reader: Box<dyn Read> = Box::new(BufReader::new(file));
-    let transactions = Parser::parse(&mut input_reader, args.input_format)
-        .map_err(|e| format!("Parsing error: {}", e))?;
-    let file = File::create(&args.output)
-        .map_err(|e| format!("File can not be created {}: {}", &args.output.display(), e))?;
-    let mut output_writer: Box<dyn Write> = Box::new(BufWriter::new(file));
-    Parser::write(&transactions, &mut output_writer, args.output_format)
-        .map_err(|e| format!("Write error: {}", e))?;
+    let mut args = Args::parse();
+    let mut input_dialect = csv_dialect(&args).unwrap_or_default();
+    if let Some(path) = &args.mapping {
+        let mapping = CsvMapping::load(path)
+            .map_err(|e| format!("Mapping can not be loaded {}: {}", path.display(), e))?;
+        input_dialect = mapping.dialect();
+        args.input_format = Arc::new(mapping);
+    }
+    if let Some(dialect) = csv_dialect(&args) {
+        let dialect: Arc<dyn TransactionFormat> = Arc::new(dialect);
+        if args.input_format.name() == Format::Csv.to_string() {
+            args.input_format = dialect.clone();
+        }
+        if args.output_format.name() == Format::Csv.to_string() {
+            args.output_format = dialect;
+        }
+    }
+    let input_reader = open_input(&args.input)?;
+    let mut output = Output::create(&args.output)?;
+    match convert_output(&args, input_reader, &mut output) {
+        Ok(()) => output.commit()?,
+        Err(ConvertError::Parse(error)) => {
+            output.discard();
+            report_parse_error(&error, &args.input, &input_dialect);
+        }
+        Err(ConvertError::Write(error)) => {
+            output.discard();
+            return Err(format!("Write error: {}", error).into());
+        }
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Reason of a failed conversion
+enum ConvertError {
+    /// Input record can not be parsed
+    Parse(ParseError),
+    /// Output can not be written
+    Write(ParseError),
+}
+
+/// Converts the input into the output, compressing it if requested
+///
+/// The compressed stream is completed even after an error, so the written part stays readable.
+fn convert_output(args: &Args, input_reader: Box<dyn Read>, output: &mut Output) -> Result<(), ConvertError> {
+    #[cfg(feature = "compression")]
+    if let Some(compression) = args.compress {
+        let mut compressed_writer = compress(output, compression).map_err(ConvertError::Write)?;
+        let result = convert(args, input_reader, &mut compressed_writer);
+        let finished = compressed_writer.finish().map(drop).map_err(ConvertError::Write);
+        return result.and(finished);
+    }
+    convert(args, input_reader, output)
+}
+
+/// Streams transactions from the input into the output in the chosen formats
+fn convert(args: &Args, input_reader: Box<dyn Read>, output_writer: &mut dyn Write) -> Result<(), ConvertError> {
+    let read_options = ReadOptions {
+        default_currency: args.default_currency.unwrap_or(DEFAULT_CURRENCY),
+        strict_descriptions: args.strict_descriptions,
+    };
+    let write_options = WriteOptions {
+        currencies: args.write_currencies,
+    };
+    let mut transaction_writer = Parser::writer_with_options(output_writer, &args.output_format, &write_options);
+    for transaction in Parser::iter_with_options(input_reader, &args.input_format, &read_options) {
+        let transaction = transaction.map_err(ConvertError::Parse)?;
+        transaction_writer.write_one(&transaction).map_err(ConvertError::Write)?;
+    }
+    transaction_writer.finish().map_err(ConvertError::Write)
+}
+
+/// Builds the CSV dialect from the command line options
+///
+/// Returns `None` if no CSV options are given, so the built-in YPBankCsv format is used.
+fn csv_dialect(args: &Args) -> Option<CsvDialect> {
+    let default = CsvDialect::default();
+    let columns = match &args.csv_columns {
+        Some(columns) => Some(columns.clone()),
+        None => args.csv_no_header.then(CsvDialect::default_columns),
+    };
+    let dialect = CsvDialect {
+        delimiter: args.csv_delimiter.unwrap_or(default.delimiter),
+        quote: args.csv_quote.unwrap_or(default.quote),
+        header_aliases: args.csv_header_aliases.iter().cloned().collect(),
+        columns,
+    };
+    (dialect != default).then_some(dialect)
+}
+
+/// Parses a single ASCII character of the CSV dialect, `\t` stands for tab
+fn parse_csv_char(value: &str) -> Result<u8, String> {
+    match value.as_bytes() {
+        b"\\t" => Ok(b'\t'),
+        [byte] if byte.is_ascii() => Ok(*byte),
+        _ => Err(format!("expected a single ASCII character, got '{}'", value)),
+    }
+}
+
+/// Parses a CSV header alias in the form `ALIAS=FIELD`
+fn parse_header_alias(value: &str) -> Result<(String, String), String> {
+    let (alias, field) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected ALIAS=FIELD, got '{}'", value))?;
+    Ok((alias.trim().to_string(), field.trim().to_uppercase()))
+}
+
+/// Path, which stands for standard input or standard output
+const STDIO_PATH: &str = "-";
+
+/// Checks if the path stands for standard input or standard output
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO_PATH
+}
+
+/// Opens the input file or standard input for buffered reading
+fn open_input(path: &Path) -> Result<Box<dyn Read>, String> {
+    if is_stdio(path) {
+        return Ok(Box::new(BufReader::new(std::io::stdin().lock())));
+    }
+    let file = File::open(path)
+        .map_err(|e| format!("File can not be opened {}: {}", path.display(), e))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Output of the conversion
+///
+/// A file is written into a temporary file next to it and replaces the output file
+/// only after the whole conversion succeeds, so a failed conversion leaves it untouched
+/// and the input file can be the output file as well.
+enum Output {
+    Stdout(BufWriter<StdoutLock<'static>>),
+    File {
+        writer: BufWriter<NamedTempFile>,
+        path: PathBuf,
+    },
+}
+
+impl Output {
+    /// Creates the temporary output file or takes standard output for buffered writing
+    fn create(path: &Path) -> Result<Self, String> {
+        if is_stdio(path) {
+            return Ok(Self::Stdout(BufWriter::new(std::io::stdout().lock())));
+        }
+        let error = |e: std::io::Error| format!("File can not be created {}: {}", path.display(), e);
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut builder = tempfile::Builder::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let permissions = std::fs::metadata(path)
+                .map(|metadata| metadata.permissions())
+                .unwrap_or_else(|_| std::fs::Permissions::from_mode(0o666));
+            builder.permissions(permissions);
+        }
+        let file = builder.tempfile_in(directory).map_err(error)?;
+        Ok(Self::File { writer: BufWriter::new(file), path: path.to_path_buf() })
+    }
+
+    /// Flushes the output and replaces the output file with the written one
+    fn commit(self) -> Result<(), String> {
+        match self {
+            Self::Stdout(mut writer) => writer.flush().map_err(|e| format!("Write error: {}", e)),
+            Self::File { writer, path } => {
+                let file = writer.into_inner().map_err(|e| format!("Write error: {}", e.error()))?;
+                file.persist(&path)
+                    .map(drop)
+                    .map_err(|e| format!("File can not be created {}: {}", path.display(), e.error))
+            }
+        }
+    }
+
+    /// Flushes the part written to standard output, or removes the temporary file
+    fn discard(self) {
+        if let Self::Stdout(mut writer) = self {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Stdout(writer) => writer.write(buf),
+            Self::File { writer, .. } => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Stdout(writer) => writer.flush(),
+            Self::File { writer, .. } => writer.flush(),
+        }
+    }
+}
+
+/// Prints the parse error against the decompressed input file content and exits
+///
+/// Standard input can not be read again, so for it only the error message and its location are printed.
+fn report_parse_error(error: &ParseError, path: &Path, dialect: &CsvDialect) -> ! {
+    if is_stdio(path) {
+        eprint!("{}", render_message(error, "<stdin>"));
+    } else {
+        let source = File::open(path)
+            .ok()
+            .and_then(|file| decompress_to_vec(file).ok())
+            .unwrap_or_default();
+        eprint!("{}", render_with_dialect(error, &path.display().to_string(), &source, dialect));
+    }
+    std::process::exit(1);
+}
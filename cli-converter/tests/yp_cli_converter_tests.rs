@@ -3,10 +3,12 @@ use financial_parser::model::transaction_type::TransactionType;
 use std::io::Cursor;
 use tempfile::NamedTempFile;
 use std::fs;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::io::Write;
 use financial_parser::parser::Parser;
 use financial_parser::format::Format;
+use financial_parser::model::currency::CurrencyCode;
+use financial_parser::model::money::Money;
 use financial_parser::model::transaction::Transaction;
 
 #[test]
@@ -17,7 +19,7 @@ fn test_cli_converter_csv_to_binary() -> Result<(), Box<dyn std::error::Error>>
             tx_type: TransactionType::Deposit,
             from_user_id: 0,
             to_user_id: 501,
-            amount: 50000,
+            amount: Money::new(50000, CurrencyCode::USD),
             timestamp: 1672531200000,
             status: TransactionStatus::Success,
             description: "Initial deposit".to_string(),
@@ -27,7 +29,7 @@ fn test_cli_converter_csv_to_binary() -> Result<(), Box<dyn std::error::Error>>
             tx_type: TransactionType::Transfer,
             from_user_id: 501,
             to_user_id: 502,
-            amount: 15000,
+            amount: Money::new(15000, CurrencyCode::USD),
             timestamp: 1672534800000,
             status: TransactionStatus::Failure,
             description: "Payment".to_string(),
@@ -70,7 +72,7 @@ fn test_cli_converter_csv_to_text_to_csv() -> Result<(), Box<dyn std::error::Err
             tx_type: TransactionType::Deposit,
             from_user_id: 0,
             to_user_id: 501,
-            amount: 50000,
+            amount: Money::new(50000, CurrencyCode::USD),
             timestamp: 1672531200000,
             status: TransactionStatus::Success,
             description: "Initial deposit".to_string(),
@@ -120,4 +122,309 @@ fn test_cli_converter_csv_to_text_to_csv() -> Result<(), Box<dyn std::error::Err
     let final_transactions = Parser::parse(&mut cursor, Format::Csv)?;
     assert_eq!(transactions, final_transactions);
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_cli_converter_stdin_to_stdout() -> Result<(), Box<dyn std::error::Error>> {
+    let transactions = vec![
+        Transaction {
+            tx_id: 1001,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 501,
+            amount: Money::new(50000, CurrencyCode::USD),
+            timestamp: 1672531200000,
+            status: TransactionStatus::Success,
+            description: "Initial deposit".to_string(),
+        }
+    ];
+    let mut input = Vec::new();
+    Parser::write(&transactions, &mut input, Format::Binary)?;
+    let mut child = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--bin",
+            "cli-converter",
+            "--",
+            "--input",
+            "-",
+            "--input-format",
+            "binary",
+            "--output",
+            "-",
+            "--output-format",
+            "csv",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(&input)?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+    let mut cursor = Cursor::new(output.stdout);
+    let parsed_transactions = Parser::parse(&mut cursor, Format::Csv)?;
+    assert_eq!(transactions, parsed_transactions);
+
+    let mut child = Command::new("cargo")
+        .args(["run", "--quiet", "--bin", "cli-converter", "--", "-i", "-", "-f", "binary", "-o", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(&input[..10])?;
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)?.contains(" --> <stdin>: record #1"));
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_cli_converter_compressed() -> Result<(), Box<dyn std::error::Error>> {
+    use financial_parser::compression::Compression;
+
+    let transactions = vec![
+        Transaction {
+            tx_id: 1001,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 501,
+            amount: Money::new(50000, CurrencyCode::USD),
+            timestamp: 1672531200000,
+            status: TransactionStatus::Success,
+            description: "Initial deposit".to_string(),
+        }
+    ];
+    let mut input_file = NamedTempFile::new()?;
+    Parser::write(&transactions, input_file.as_file_mut(), Format::Csv)?;
+    input_file.flush()?;
+    let compressed_file = NamedTempFile::new()?;
+    let output_file = NamedTempFile::new()?;
+    let status = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "cli-converter",
+            "--",
+            "-i",
+            input_file.path().to_str().unwrap(),
+            "-o",
+            compressed_file.path().to_str().unwrap(),
+            "-F",
+            "binary",
+            "--compress",
+            "zstd",
+        ])
+        .status()?;
+    assert!(status.success());
+    let compressed_bytes = fs::read(compressed_file.path())?;
+    assert_eq!(Compression::detect(&compressed_bytes), Some(Compression::Zstd));
+    let status = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "cli-converter",
+            "--",
+            "-i",
+            compressed_file.path().to_str().unwrap(),
+            "-o",
+            output_file.path().to_str().unwrap(),
+            "-F",
+            "csv",
+        ])
+        .status()?;
+    assert!(status.success());
+    let parsed_transactions = Parser::parse(fs::File::open(output_file.path())?, Format::Csv)?;
+    assert_eq!(transactions, parsed_transactions);
+    Ok(())
+}
+
+#[test]
+fn test_cli_converter_csv_dialect() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = NamedTempFile::new()?;
+    input_file.write_all("Сумма;TX_ID;TX_TYPE;FROM_USER_ID;TO_USER_ID;TIMESTAMP;STATUS;DESCRIPTION\n\
+        50000;1001;DEPOSIT;0;501;1672531200000;SUCCESS;\"Initial; deposit\"\n".as_bytes())?;
+    input_file.flush()?;
+    let output_file = NamedTempFile::new()?;
+    let status = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "cli-converter",
+            "--",
+            "-i",
+            input_file.path().to_str().unwrap(),
+            "-f",
+            "csv",
+            "--csv-delimiter",
+            ";",
+            "--csv-header-alias",
+            "Сумма=AMOUNT",
+            "-o",
+            output_file.path().to_str().unwrap(),
+            "-F",
+            "json",
+        ])
+        .status()?;
+    assert!(status.success());
+    let transactions = Parser::parse(fs::File::open(output_file.path())?, Format::Json)?;
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].amount, Money::new(50000, CurrencyCode::USD));
+    assert_eq!(transactions[0].description, "Initial; deposit");
+    Ok(())
+}
+
+#[test]
+fn test_cli_converter_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let mut mapping_file = tempfile::Builder::new().suffix(".toml").tempfile()?;
+    mapping_file.write_all(b"user_id = 501\n[columns]\ndate = \"Date\"\ndebit = \"Debit\"\ncredit = \"Credit\"\ndescription = \"Payee\"\n")?;
+    mapping_file.flush()?;
+    let mut input_file = NamedTempFile::new()?;
+    input_file.write_all(b"Date,Payee,Debit,Credit\n2023-01-01,Salary,,500.00\n2023-01-02,ATM,20.00,\n")?;
+    input_file.flush()?;
+    let output_file = NamedTempFile::new()?;
+    let status = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "cli-converter",
+            "--",
+            "-i",
+            input_file.path().to_str().unwrap(),
+            "--mapping",
+            mapping_file.path().to_str().unwrap(),
+            "-o",
+            output_file.path().to_str().unwrap(),
+            "-F",
+            "csv",
+        ])
+        .status()?;
+    assert!(status.success());
+    let transactions = Parser::parse(fs::File::open(output_file.path())?, Format::Csv)?;
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0].tx_type, TransactionType::Deposit);
+    assert_eq!(transactions[0].amount, Money::new(50000, CurrencyCode::USD));
+    assert_eq!(transactions[1].tx_type, TransactionType::Withdrawal);
+    assert_eq!(transactions[1].from_user_id, 501);
+    Ok(())
+}
+
+#[test]
+fn test_cli_converter_keeps_output_on_error() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = NamedTempFile::new()?;
+    input_file.write_all(b"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+        1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,\"Initial account funding\"\n\
+        1002,TRANSFER,501,502,15000,1672534800000,DONE,\"Unknown status\"\n")?;
+    input_file.flush()?;
+    let mut output_file = NamedTempFile::new()?;
+    output_file.write_all(b"previous output")?;
+    output_file.flush()?;
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "cli-converter",
+            "--",
+            "-i",
+            input_file.path().to_str().unwrap(),
+            "-o",
+            output_file.path().to_str().unwrap(),
+            "-F",
+            "json",
+        ])
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)?.contains("Invalid transaction status: DONE"));
+    assert_eq!(fs::read(output_file.path())?, b"previous output");
+
+    let mut valid_file = NamedTempFile::new()?;
+    valid_file.write_all(b"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+        1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,\"Initial account funding\"\n")?;
+    valid_file.flush()?;
+    let path = valid_file.path().to_str().unwrap();
+    let status = Command::new("cargo")
+        .args(["run", "--bin", "cli-converter", "--", "-i", path, "-o", path, "-F", "jsonl"])
+        .status()?;
+    assert!(status.success());
+    let transactions = Parser::parse(fs::File::open(path)?, Format::JsonLines)?;
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].tx_id, 1001);
+    Ok(())
+}
+
+#[test]
+fn test_cli_converter_strict_descriptions() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = NamedTempFile::new()?;
+    input_file.write_all(b"TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\nAMOUNT: 50000\n\
+        TIMESTAMP: 1672531200000\nSTATUS: SUCCESS\nDESCRIPTION: \"C:\\temp\"\n")?;
+    input_file.flush()?;
+    let path = input_file.path().to_str().unwrap();
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--bin", "cli-converter", "--", "-i", path, "-f", "text", "-o", "-", "-F", "jsonl"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)?.contains(r#""description":"C:\\temp""#));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--bin",
+            "cli-converter",
+            "--",
+            "-i",
+            path,
+            "-f",
+            "text",
+            "-o",
+            "-",
+            "--strict-descriptions",
+        ])
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)?.contains("Invalid escape in description"));
+    Ok(())
+}
+
+#[test]
+fn test_cli_converter_mixed_currencies() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = NamedTempFile::new()?;
+    input_file.write_all(b"TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\nAMOUNT: 50000\n\
+        TIMESTAMP: 1672531200000\nSTATUS: SUCCESS\nDESCRIPTION: \"Dollars\"\n\n\
+        TX_ID: 1002\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\nAMOUNT: 7000\nCURRENCY: EUR\n\
+        TIMESTAMP: 1672531200000\nSTATUS: SUCCESS\nDESCRIPTION: \"Euros\"\n")?;
+    input_file.flush()?;
+    let path = input_file.path().to_str().unwrap();
+    for format in ["csv", "binary"] {
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--bin", "cli-converter", "--", "-i", path, "-f", "text", "-o", "-", "-F", format])
+            .output()?;
+        assert_eq!(output.status.code(), Some(1));
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--bin",
+                "cli-converter",
+                "--",
+                "-i",
+                path,
+                "-f",
+                "text",
+                "-o",
+                "-",
+                "-F",
+                format,
+                "--write-currencies",
+            ])
+            .output()?;
+        assert!(output.status.success());
+        let format = if format == "csv" { Format::Csv } else { Format::Binary };
+        let transactions = Parser::parse(Cursor::new(output.stdout), format)?;
+        let currencies: Vec<CurrencyCode> = transactions.iter().map(|t| t.amount.currency).collect();
+        assert_eq!(currencies, vec![CurrencyCode::USD, CurrencyCode::EUR]);
+    }
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use crate::errors::ParseError;
+#[cfg(not(feature = "compression"))]
+use crate::errors::ParseErrorKind;
+use clap::ValueEnum;
+use std::io::{Cursor, Read};
+#[cfg(feature = "compression")]
+use std::io::Write;
+use strum_macros::{Display, EnumString};
+
+/// "Magic" bytes of a gzip member
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// "Magic" bytes of a zstd frame
+pub const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Supported compression formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[derive(EnumString, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Compression {
+    /// gzip, files with `.gz` extension
+    Gzip,
+    /// Zstandard, files with `.zst` extension
+    Zstd,
+}
+
+impl Compression {
+    /// Detects compression of data by its "magic" bytes
+    ///
+    /// # Parameters
+    ///
+    /// * `head` — first bytes of the data
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<Compression>`:
+    /// - `Some(Compression)` — data is compressed
+    /// - `None` — data is not compressed or compression is unknown
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        if head.starts_with(&GZIP_MAGIC) {
+            Some(Self::Gzip)
+        } else if head.starts_with(&ZSTD_MAGIC) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+}
+
+/// Wraps the reader into a decompressor, if the data starts with "magic" bytes of a compression format
+///
+/// Concatenated gzip members and zstd frames are decompressed as a single stream,
+/// uncompressed data is passed through as is.
+///
+/// # Parameters
+///
+/// * `reader` — any type, implementing `std::io::Read`, from which data can be read
+///
+/// # Returning value
+///
+/// Returns `Result<Box<dyn Read>, ParseError>`:
+/// - `Ok(Box<dyn Read>)` — reader of the decompressed data
+/// - `Err(ParseError)` — I/O error or the `compression` feature is disabled
+pub fn decompress<'a, R: Read + 'a>(mut reader: R) -> Result<Box<dyn Read + 'a>, ParseError> {
+    let mut head = Vec::with_capacity(ZSTD_MAGIC.len());
+    reader.by_ref().take(ZSTD_MAGIC.len() as u64).read_to_end(&mut head)?;
+    let compression = Compression::detect(&head);
+    let reader = PrefixedReader { head: Cursor::new(head), inner: reader, error: None };
+    match compression {
+        None => Ok(Box::new(reader)),
+        #[cfg(feature = "compression")]
+        Some(Compression::Gzip) => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "compression")]
+        Some(Compression::Zstd) => Ok(Box::new(zstd::stream::read::Decoder::new(reader)?)),
+        #[cfg(not(feature = "compression"))]
+        Some(compression) => Err(ParseErrorKind::UnsupportedCompression(compression).into()),
+    }
+}
+
+/// Reader, which returns already consumed head bytes before the rest of the data
+///
+/// Unlike `Read::chain`, fills the buffer from both parts in one call,
+/// so the first read is not limited to the few head bytes. An error of the inner reader,
+/// raised after some head bytes were returned, is kept and returned by the next read.
+struct PrefixedReader<R: Read> {
+    head: Cursor<Vec<u8>>,
+    inner: R,
+    error: Option<std::io::Error>,
+}
+
+impl<R: Read> Read for PrefixedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        let head_size = self.head.read(buf)?;
+        if head_size == 0 {
+            return self.inner.read(buf);
+        }
+        if head_size == buf.len() {
+            return Ok(head_size);
+        }
+        match self.inner.read(&mut buf[head_size..]) {
+            Ok(size) => Ok(head_size + size),
+            Err(error) => {
+                self.error = Some(error);
+                Ok(head_size)
+            }
+        }
+    }
+}
+
+/// Reads the whole input, decompressing it if needed
+///
+/// # Parameters
+///
+/// * `reader` — any type, implementing `std::io::Read`, from which data can be read
+///
+/// # Returning value
+///
+/// Returns `Result<Vec<u8>, ParseError>`:
+/// - `Ok(Vec<u8>)` — decompressed data
+/// - `Err(ParseError)` — I/O or decompression error
+pub fn decompress_to_vec<R: Read>(reader: R) -> Result<Vec<u8>, ParseError> {
+    let mut data = Vec::new();
+    decompress(reader)?.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Wraps the writer into a compressor
+///
+/// # Parameters
+///
+/// * `writer` — any type, implementing `std::io::Write`, into which compressed data will be written
+/// * `compression` — compression format
+///
+/// # Returning value
+///
+/// Returns `Result<CompressedWriter<W>, ParseError>`:
+/// - `Ok(CompressedWriter<W>)` — writer, compressing the data
+/// - `Err(ParseError)` — compressor can not be created
+#[cfg(feature = "compression")]
+pub fn compress<W: Write>(writer: W, compression: Compression) -> Result<CompressedWriter<W>, ParseError> {
+    let encoder = match compression {
+        Compression::Gzip => {
+            Encoder::Gzip(flate2::write::GzEncoder::new(writer, flate2::Compression::default()))
+        }
+        Compression::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(writer, 0)?),
+    };
+    Ok(CompressedWriter { encoder })
+}
+
+/// Compressors of the supported formats
+#[cfg(feature = "compression")]
+enum Encoder<W: Write> {
+    Gzip(flate2::write::GzEncoder<W>),
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+}
+
+/// Writer, compressing the data
+///
+/// `finish` must be called after the last write, otherwise the compressed stream is incomplete.
+#[cfg(feature = "compression")]
+pub struct CompressedWriter<W: Write> {
+    encoder: Encoder<W>,
+}
+
+#[cfg(feature = "compression")]
+impl<W: Write> CompressedWriter<W> {
+    /// Completes the compressed stream and flushes the underlying writer
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<W, ParseError>`:
+    /// - `Ok(W)` — the underlying writer
+    /// - `Err(ParseError)` — write error
+    pub fn finish(self) -> Result<W, ParseError> {
+        let mut writer = match self.encoder {
+            Encoder::Gzip(encoder) => encoder.finish()?,
+            Encoder::Zstd(encoder) => encoder.finish()?,
+        };
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+#[cfg(feature = "compression")]
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.encoder {
+            Encoder::Gzip(encoder) => encoder.write(buf),
+            Encoder::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.encoder {
+            Encoder::Gzip(encoder) => encoder.flush(),
+            Encoder::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::errors::{Location, ParseError};
+use crate::format::yp_bank_csv_format::CsvDialect;
+
+/// Number of bytes shown in a single hex dump row
+const HEX_ROW_SIZE: usize = 16;
+
+/// Renders a parse error against the original input
+///
+/// For text and CSV input the offending line is shown with a caret under the bad field,
+/// for binary input a hex dump around the failing offset is shown.
+/// If the location points nowhere in the input, only the error message is rendered.
+/// CSV input is expected in the default YPBankCsv dialect, see `render_with_dialect`.
+///
+/// # Parameters
+///
+/// * `error` — error to render
+/// * `source_name` — input name shown in the report, e.g. file path
+/// * `source` — original input, from which the error was raised
+///
+/// # Returning value
+///
+/// Returns `String` - multiline human readable report
+pub fn render(error: &ParseError, source_name: &str, source: &[u8]) -> String {
+    render_with_dialect(error, source_name, source, &CsvDialect::default())
+}
+
+/// Renders a parse error against the original input, read in the chosen CSV dialect
+///
+/// CSV fields are split by the delimiter and quote of the dialect and found
+/// by the header line, including its aliases, or by the dialect columns,
+/// if the data has no header. Other formats are rendered the same as by `render`.
+///
+/// # Parameters
+///
+/// * `error` — error to render
+/// * `source_name` — input name shown in the report, e.g. file path
+/// * `source` — original input, from which the error was raised
+/// * `dialect` — dialect of CSV input
+///
+/// # Returning value
+///
+/// Returns `String` - multiline human readable report
+pub fn render_with_dialect(error: &ParseError, source_name: &str, source: &[u8], dialect: &CsvDialect) -> String {
+    let location = error.location();
+    let mut report = render_message(error, source_name);
+    if location.is_empty() {
+        return report;
+    }
+    if let Some(line_number) = location.line {
+        if let Some(snippet) = render_line(location, line_number, source, dialect) {
+            report.push_str(&snippet);
+        }
+    } else if let Some(byte_offset) = location.byte_offset {
+        report.push_str(&render_hex_dump(byte_offset, source));
+    }
+    report
+}
+
+/// Renders a parse error without the original input
+///
+/// Used when the input can not be read again, e.g. when it came from standard input.
+///
+/// # Parameters
+///
+/// * `error` — error to render
+/// * `source_name` — input name shown in the report
+///
+/// # Returning value
+///
+/// Returns `String` - error message with its location
+pub fn render_message(error: &ParseError, source_name: &str) -> String {
+    let location = error.location();
+    if location.is_empty() {
+        format!("error: {}\n --> {}\n", error.kind(), source_name)
+    } else {
+        format!("error: {}\n --> {}: {}\n", error.kind(), source_name, location)
+    }
+}
+
+/// Renders the line with a caret under the field, pointed by the location
+fn render_line(location: &Location, line_number: usize, source: &[u8], dialect: &CsvDialect) -> Option<String> {
+    let line = source_line(source, line_number)?;
+    let gutter = " ".repeat(line_number.to_string().len());
+    let mut snippet = format!("{} |\n{} | {}\n", gutter, line_number, line);
+    let span = match location.column {
+        Some(column) => Some(text_span(&line, column)),
+        None => location.field.as_deref().and_then(|field| csv_span(source, &line, field, dialect)),
+    };
+    if let Some((start, width)) = span {
+        let padding: String = line
+            .chars()
+            .take(start)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        snippet.push_str(&format!("{} | {}{}\n", gutter, padding, "^".repeat(width.max(1))));
+    }
+    Some(snippet)
+}
+
+/// Returns the one-based line of the input without the line terminator
+fn source_line(source: &[u8], line_number: usize) -> Option<String> {
+    let line = source.split(|&b| b == b'\n').nth(line_number.checked_sub(1)?)?;
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    Some(String::from_utf8_lossy(line).into_owned())
+}
+
+/// Computes the span from the one-based column up to the end of the line content
+///
+/// # Returning value
+///
+/// Returns `(usize, usize)` - zero-based start and width in chars
+fn text_span(line: &str, column: usize) -> (usize, usize) {
+    let start = column.saturating_sub(1);
+    let content_width = line.trim_end().chars().count();
+    (start, content_width.saturating_sub(start))
+}
+
+/// Computes the span of the CSV field, found by its name in the header line
+/// or in the dialect columns
+///
+/// # Returning value
+///
+/// Returns `Option<(usize, usize)>`:
+/// - `Some((usize, usize))` — zero-based start and width in chars
+/// - `None` — header or field is not found
+fn csv_span(source: &[u8], line: &str, field: &str, dialect: &CsvDialect) -> Option<(usize, usize)> {
+    let quote = char::from(dialect.quote);
+    let is_field = |name: &str| {
+        let name = name.trim().trim_matches(quote);
+        name == field || dialect.header_aliases.get(name).is_some_and(|alias| alias == field)
+    };
+    let index = match &dialect.columns {
+        Some(columns) => columns.iter().position(|name| is_field(name))?,
+        None => {
+            let header = source_line(source, 1)?;
+            let header = header.trim_start_matches('\u{feff}');
+            split_csv_line(header, dialect).iter().position(|&(start, width)| {
+                let name: String = header.chars().skip(start).take(width).collect();
+                is_field(&name)
+            })?
+        }
+    };
+    split_csv_line(line, dialect).get(index).copied()
+}
+
+/// Splits the CSV line into fields, respecting quotes of the dialect
+///
+/// # Returning value
+///
+/// Returns `Vec<(usize, usize)>` - zero-based start and width in chars of every field
+fn split_csv_line(line: &str, dialect: &CsvDialect) -> Vec<(usize, usize)> {
+    let delimiter = char::from(dialect.delimiter);
+    let quote = char::from(dialect.quote);
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut position = 0;
+    for c in line.chars() {
+        match c {
+            c if c == quote => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => {
+                fields.push((start, position - start));
+                start = position + 1;
+            }
+            _ => {}
+        }
+        position += 1;
+    }
+    fields.push((start, position - start));
+    fields
+}
+
+/// Renders rows of the input around the byte offset with a marker under the failing byte
+fn render_hex_dump(byte_offset: u64, source: &[u8]) -> String {
+    let offset = usize::try_from(byte_offset).unwrap_or(usize::MAX);
+    // the window is clamped to the data, so an offset past the end shows the last rows
+    let offset_row = offset.min(source.len().saturating_sub(1)) / HEX_ROW_SIZE;
+    let first_row = offset_row.saturating_sub(1);
+    let mut dump = String::from("  |\n");
+    for row in first_row..=offset_row.saturating_add(1) {
+        let row_start = row.saturating_mul(HEX_ROW_SIZE);
+        if row_start >= source.len() {
+            break;
+        }
+        let bytes = &source[row_start..row_start.saturating_add(HEX_ROW_SIZE).min(source.len())];
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = bytes
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+            .collect();
+        dump.push_str(&format!(
+            "  | {:08x}  {:<width$}  |{}|\n",
+            row_start,
+            hex.join(" "),
+            ascii,
+            width = HEX_ROW_SIZE * 3 - 1
+        ));
+        if row == offset_row && offset < source.len() {
+            let padding = " ".repeat((offset - row_start) * 3);
+            dump.push_str(&format!("  | {:8}  {}^^\n", "", padding));
+        }
+    }
+    if offset >= source.len() {
+        dump.push_str(&format!("  = note: offset {} is past the end of input ({} bytes)\n", byte_offset, source.len()));
+    }
+    dump
+}
@@ -1,7 +1,9 @@
+use std::fmt;
 use thiserror::Error;
+use crate::compression::Compression;
 use crate::model::transaction_type::TransactionType;
 
-/// Errors raised when parsing transactions from different formats
+/// Kinds of errors raised when parsing transactions from different formats
 ///
 /// This enum describes all possible errors which can be raised when:
 /// - reading file (I/O),
@@ -9,19 +11,58 @@ use crate::model::transaction_type::TransactionType;
 /// - validating data,
 /// - deserializing binary data.
 #[derive(Error, Debug)]
-pub enum ParseError {
+pub enum ParseErrorKind {
     /// Input output error (e.g, file not found or not readable).
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
     /// CSV file parsing error.
     #[error("CSV error: {0}")]
     Csv(#[from] csv::Error),
+    /// JSON parsing error.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// JSON syntax error in a line of JSON Lines, the next lines can still be read.
+    #[error("Invalid JSON line: {0}")]
+    JsonLine(serde_json::Error),
     /// Transaction type parsing error.
-    #[error("Transaction type parsing error: {0}")]
+    #[error("Invalid transaction type: {0}")]
     InvalidTransactionType(String),
     /// Transaction status parsing error.
-    #[error("Transaction status parsing error: {0}")]
+    #[error("Invalid transaction status: {0}")]
     InvalidTransactionStatus(String),
+    /// Number parsing error.
+    ///
+    /// Contains:
+    /// - `String` — actual value
+    /// - `String` — parsing error description
+    #[error("Invalid number '{0}': {1}")]
+    InvalidNumber(String, String),
+    /// Required field is absent in a record.
+    #[error("Missing required field '{0}'")]
+    MissingField(String),
+    /// Currency code is not listed in ISO 4217.
+    #[error("Invalid currency code: {0}")]
+    InvalidCurrency(String),
+    /// Field occurs more than once in a record.
+    #[error("Duplicate key '{0}'")]
+    DuplicateField(String),
+    /// Line does not follow `KEY: value` format.
+    #[error("Invalid line format: '{0}'")]
+    InvalidLineFormat(String),
+    /// Description is not enclosed in double quotes.
+    #[error("DESCRIPTION must be in double quotes: {0}")]
+    UnquotedDescription(String),
+    /// Description contains an escape sequence or a quote, which is not allowed in strict mode.
+    #[error("Invalid escape in description: {0}")]
+    InvalidEscape(String),
+    /// User id does not conform to the transaction type.
+    ///
+    /// Contains:
+    /// - `&'static str` — user id field name
+    /// - `u64` — actual user id
+    /// - `TransactionType` — transaction type
+    #[error("{0} must be 0 for {2}, got {1}")]
+    InvalidUserId(&'static str, u64, TransactionType),
     /// Transaction data validation error
     #[error("Validation error: {0}")]
     Validation(String),
@@ -46,6 +87,25 @@ pub enum ParseError {
     /// - `[u8; 4]` — required "magic" value
     #[error("Invalid magic: {0:?}, expected: {1:?}")]
     InvalidMagic([u8; 4], [u8; 4]),
+    /// Binary file version is not supported.
+    #[error("Unsupported file version: {0}")]
+    UnsupportedVersion(u16),
+    /// Binary file header contains unknown flags.
+    #[error("Unsupported file flags: {0:#06x}")]
+    UnsupportedFlags(u16),
+    /// Corrupted binary data has been skipped while searching for the next record.
+    #[error("Skipped corrupted bytes {0}..{1}")]
+    SkippedBytes(u64, u64),
+    /// Binary record checksum does not match its content.
+    #[error("Checksum mismatch: expected {0:#010x}, actual {1:#010x}")]
+    ChecksumMismatch(u32, u32),
+    /// Binary record size does not match its content.
+    ///
+    /// Contains:
+    /// - `u32` — expected record size
+    /// - `u32` — actual record size
+    #[error("Invalid data size: expected {0}, actual {1}")]
+    InvalidRecordSize(u32, u32),
     /// Invalid amount for a transaction type.
     ///
     /// Contains:
@@ -62,5 +122,294 @@ pub enum ParseError {
     /// Invaild data error.
     #[error("Invalid data: {0}")]
     InvalidData(String),
+    /// Requested format is not registered.
+    ///
+    /// Contains:
+    /// - `String` — requested format name
+    /// - `Vec<String>` — registered format names
+    #[error("Unknown format: {0}, available formats: {1:?}")]
+    UnknownFormat(String, Vec<String>),
+    /// Input format can not be detected automatically.
+    #[error("Input format can not be detected, specify it explicitly")]
+    FormatNotDetected,
+    /// Input is compressed, but the library is built without the `compression` feature.
+    #[error("Compressed input is not supported: {0}, enable the `compression` feature")]
+    UnsupportedCompression(Compression),
+    /// CSV mapping config is invalid.
+    #[error("Invalid mapping: {0}")]
+    InvalidMapping(String),
+    /// Date does not match any of the expected formats.
+    ///
+    /// Contains:
+    /// - `String` — actual value
+    /// - `Vec<String>` — expected formats
+    #[error("Invalid date '{0}', expected formats: {1:?}")]
+    InvalidDate(String, Vec<String>),
+    /// Format can not be used for writing.
+    #[error("Format {0} can only be used for reading")]
+    ReadOnlyFormat(String),
+}
+
+impl ParseErrorKind {
+    /// Checks if parsing can continue with the next record after this error
+    ///
+    /// # Returning value
+    ///
+    /// Returns `bool`:
+    /// - `true` — error concerns a single record, which can be skipped
+    /// - `false` — error breaks the whole input (I/O, headers, record framing, JSON syntax)
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Self::Io(_)
+            | Self::InvalidHeader(..)
+            | Self::InvalidMagic(..)
+            | Self::UnsupportedVersion(_)
+            | Self::UnsupportedFlags(_)
+            | Self::UnknownFormat(..)
+            | Self::FormatNotDetected
+            | Self::UnsupportedCompression(_)
+            | Self::InvalidMapping(_)
+            | Self::ReadOnlyFormat(_) => false,
+            Self::Csv(e) => !e.is_io_error(),
+            Self::Json(e) => e.is_data(),
+            Self::InvalidTransactionType(_)
+            | Self::InvalidTransactionStatus(_)
+            | Self::InvalidNumber(..)
+            | Self::InvalidDate(..)
+            | Self::JsonLine(_)
+            | Self::InvalidCurrency(_)
+            | Self::MissingField(_)
+            | Self::DuplicateField(_)
+            | Self::InvalidLineFormat(_)
+            | Self::UnquotedDescription(_)
+            | Self::InvalidEscape(_)
+            | Self::InvalidUserId(..)
+            | Self::Validation(_)
+            | Self::InvalidRecord(..)
+            | Self::InvalidRecordSize(..)
+            | Self::ChecksumMismatch(..)
+            | Self::SkippedBytes(..)
+            | Self::InvalidAmountForTransactionType(..)
+            | Self::InvalidAmount(_)
+            | Self::InvalidDescription(_)
+            | Self::InvalidData(_) => true,
+        }
+    }
 }
 
+/// Position in the input, where an error occurred
+///
+/// Every part is optional, as different formats and errors
+/// can point to the input with different precision.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    /// Zero-based index of the record in the input
+    pub record_index: Option<usize>,
+    /// One-based line number in text formats
+    pub line: Option<usize>,
+    /// One-based column number in text formats
+    pub column: Option<usize>,
+    /// Zero-based offset in bytes from the beginning of the input
+    pub byte_offset: Option<u64>,
+    /// Field name
+    pub field: Option<String>,
+}
+
+impl Location {
+    /// Checks if no part of the location is known
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fills parts of the location, which are not set yet, from `other`
+    ///
+    /// # Parameters
+    ///
+    /// * `other` — less precise location, e.g. of the enclosing record
+    pub fn merge(&mut self, other: Location) {
+        self.record_index = self.record_index.or(other.record_index);
+        self.line = self.line.or(other.line);
+        self.column = self.column.or(other.column);
+        self.byte_offset = self.byte_offset.or(other.byte_offset);
+        self.field = self.field.take().or(other.field);
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(record_index) = self.record_index {
+            parts.push(format!("record #{}", record_index + 1));
+        }
+        if let Some(line) = self.line {
+            parts.push(format!("line {}", line));
+        }
+        if let Some(column) = self.column {
+            parts.push(format!("column {}", column));
+        }
+        if let Some(byte_offset) = self.byte_offset {
+            parts.push(format!("byte offset {}", byte_offset));
+        }
+        if let Some(field) = &self.field {
+            parts.push(format!("field {}", field));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Error raised when parsing transactions from different formats
+///
+/// Consists of a matchable error kind and the location in the input,
+/// where the error occurred.
+#[derive(Debug)]
+pub struct ParseError {
+    inner: Box<ParseErrorInner>,
+}
+
+#[derive(Debug)]
+struct ParseErrorInner {
+    kind: ParseErrorKind,
+    location: Location,
+}
+
+impl ParseError {
+    /// Creates an error without location
+    ///
+    /// # Parameters
+    ///
+    /// * `kind` — error kind
+    pub fn new(kind: ParseErrorKind) -> Self {
+        Self {
+            inner: Box::new(ParseErrorInner {
+                kind,
+                location: Location::default(),
+            }),
+        }
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `&ParseErrorKind` - error kind
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.inner.kind
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `ParseErrorKind` - error kind, consuming the error
+    pub fn into_kind(self) -> ParseErrorKind {
+        self.inner.kind
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `&Location` - location in the input, where the error occurred
+    pub fn location(&self) -> &Location {
+        &self.inner.location
+    }
+
+    /// Adds location parts, which are not set yet
+    ///
+    /// # Parameters
+    ///
+    /// * `location` — location of the error
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.inner.location.merge(location);
+        self
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `&mut Location` - place of the error, which can be corrected
+    pub(crate) fn location_mut(&mut self) -> &mut Location {
+        &mut self.inner.location
+    }
+
+    /// Sets the field name, if it is not set yet
+    ///
+    /// # Parameters
+    ///
+    /// * `field` — field name
+    pub fn with_field(self, field: &str) -> Self {
+        self.with_location(Location {
+            field: Some(field.to_string()),
+            ..Location::default()
+        })
+    }
+
+    /// Checks if parsing can continue with the next record after this error
+    pub fn is_recoverable(&self) -> bool {
+        self.inner.kind.is_recoverable()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.location().is_empty() {
+            write!(f, "{}", self.kind())
+        } else {
+            write!(f, "{} at {}", self.kind(), self.location())
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(self.kind())
+    }
+}
+
+impl From<ParseErrorKind> for ParseError {
+    fn from(kind: ParseErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(error: std::io::Error) -> Self {
+        Self::new(ParseErrorKind::Io(error))
+    }
+}
+
+impl From<csv::Error> for ParseError {
+    fn from(error: csv::Error) -> Self {
+        let location = error.position().map(|position| Location {
+            line: Some(position.line() as usize),
+            byte_offset: Some(position.byte()),
+            ..Location::default()
+        });
+        Self::new(ParseErrorKind::Csv(error)).with_location(location.unwrap_or_default())
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(error: serde_json::Error) -> Self {
+        let location = match error.line() {
+            0 => Location::default(),
+            line => Location {
+                line: Some(line),
+                column: Some(error.column()),
+                ..Location::default()
+            },
+        };
+        Self::new(ParseErrorKind::Json(error)).with_location(location)
+    }
+}
+
+/// Error of a single record, rejected in lenient parsing mode
+#[derive(Debug)]
+pub struct RecordError {
+    /// Zero-based index of the record in the input, for skipped bytes the index of the following record
+    pub record_index: usize,
+    /// Reason of the record rejection
+    pub error: ParseError,
+}
+
+impl RecordError {
+    /// # Returning value
+    ///
+    /// Returns `&Location` - location of the rejected record in the input
+    pub fn location(&self) -> &Location {
+        self.error.location()
+    }
+}
@@ -6,16 +6,48 @@ pub mod yp_bank_text_format;
 ///
 /// This module contains functionality for reading and writing data in YPBankBin format
 pub mod yp_bank_bin_format;
+/// # Memory-mapped YPBankBinFormat reader module
+///
+/// This module contains zero-copy reading of data in YPBankBin format
+pub mod yp_bank_bin_mmap;
 /// # YPBankCSVFormat parser module
 ///
 /// This module contains functionality for reading and writing data in YPBankCsv format
 pub mod yp_bank_csv_format;
+/// # Mapped CSV parser module
+///
+/// This module contains functionality for reading arbitrary bank CSV exports,
+/// described by a mapping config
+pub mod csv_mapping;
+/// # JSON format parser module
+///
+/// This module contains functionality for reading and writing data in JSON format
+pub mod json_format;
+/// # JSON Lines format parser module
+///
+/// This module contains functionality for reading and writing data in JSON Lines format
+pub mod json_lines_format;
 /// # Common functionality module
 ///
 /// This module contains functionality common for several parsers
 pub mod common;
+/// # Format registry module
+///
+/// This module contains the registry of formats available by name
+pub mod registry;
 
+use crate::errors::ParseError;
+use crate::format::json_format::JsonParser;
+use crate::format::json_lines_format::JsonLinesParser;
+use crate::format::yp_bank_bin_format::YPBankBinParser;
+use crate::format::yp_bank_csv_format::YPBankCsvParser;
+use crate::format::yp_bank_text_format::YPBankTextParser;
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::transaction::Transaction;
 use clap::ValueEnum;
+use std::fmt;
+use std::io::{Read, Write};
+use std::sync::Arc;
 use strum_macros::{EnumString, Display};
 
 /// Acceptable parsing formats
@@ -28,5 +60,364 @@ pub enum Format {
     /// YPBankCsv format
     Csv, 
     /// YPBankBin format
-    Binary 
-}
\ No newline at end of file
+    Binary,
+    /// JSON array of transactions
+    Json,
+    /// JSON Lines, one transaction per line
+    #[strum(serialize = "jsonl")]
+    #[value(name = "jsonl")]
+    JsonLines,
+}
+
+impl Format {
+    /// # Returning value
+    ///
+    /// Returns `&'static dyn TransactionFormat` - implementation of the chosen built-in format
+    pub fn handler(&self) -> &'static dyn TransactionFormat {
+        match self {
+            Self::Text => &YPBankTextParser,
+            Self::Csv => &YPBankCsvParser,
+            Self::Binary => &YPBankBinParser,
+            Self::Json => &JsonParser,
+            Self::JsonLines => &JsonLinesParser,
+        }
+    }
+}
+
+/// Part of the input, which can be parsed independently of other parts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<'a> {
+    /// Bytes, which must be read before the chunk, e.g. the file header
+    pub prefix: &'a [u8],
+    /// Whole records
+    pub data: &'a [u8],
+    /// Offset of `data` from the beginning of the input
+    pub byte_offset: u64,
+    /// Number of lines before `data` in the input
+    pub line_offset: usize,
+}
+
+impl Chunk<'_> {
+    /// Converts location of an error, reported by the reader of this chunk,
+    /// into location in the whole input
+    ///
+    /// # Parameters
+    ///
+    /// * `error` — error, reported by the reader of this chunk
+    /// * `records_before` — number of records in the previous chunks
+    ///
+    /// # Returning value
+    ///
+    /// Returns `ParseError` - the same error with the corrected location
+    pub fn locate(&self, mut error: ParseError, records_before: usize) -> ParseError {
+        let location = error.location_mut();
+        if let Some(record_index) = &mut location.record_index {
+            *record_index += records_before;
+        }
+        if let Some(line) = &mut location.line {
+            *line += self.line_offset;
+        }
+        if let Some(byte_offset) = &mut location.byte_offset {
+            let prefix_len = self.prefix.len() as u64;
+            // errors inside the prefix already point into the input
+            if *byte_offset >= prefix_len {
+                *byte_offset = *byte_offset - prefix_len + self.byte_offset;
+            }
+        }
+        error
+    }
+}
+
+/// Reading settings, common for all formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Currency of amounts in inputs, which do not specify it
+    pub default_currency: CurrencyCode,
+    /// Reject descriptions with unknown escapes or unescaped quotes in text format
+    pub strict_descriptions: bool,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            default_currency: DEFAULT_CURRENCY,
+            strict_descriptions: false,
+        }
+    }
+}
+
+/// Writing settings, common for all formats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Write the currency of every amount, even in `DEFAULT_CURRENCY`: the `CURRENCY` column
+    /// in CSV and the money extension of `VERSION_2` in binary format. Without it the layout
+    /// is chosen by the first record, so amounts in other currencies can not follow it.
+    pub currencies: bool,
+}
+
+impl WriteOptions {
+    /// Chooses settings, with which all the transactions can be written
+    ///
+    /// # Parameters
+    ///
+    /// * `transactions` — transactions to write
+    ///
+    /// # Returning value
+    ///
+    /// Returns `WriteOptions` - currencies are written, if any amount is not in `DEFAULT_CURRENCY`
+    /// or has a scale other than the minor unit of its currency
+    pub fn for_transactions(transactions: &[Transaction]) -> Self {
+        Self {
+            currencies: transactions.iter().any(|transaction| {
+                transaction.amount.currency != DEFAULT_CURRENCY
+                    || transaction.amount.scale != transaction.amount.currency.minor_unit()
+            }),
+        }
+    }
+}
+
+/// Streaming iterator over parsed transactions
+pub type TransactionIter<'a> = Box<dyn Iterator<Item = Result<Transaction, ParseError>> + 'a>;
+
+/// Transaction data format
+///
+/// Implemented by every built-in format parser. Downstream crates can implement it
+/// for their own formats and add them to the `registry`, which makes them available
+/// by name in `Parser` and the command line tools.
+pub trait TransactionFormat: Send + Sync {
+    /// # Returning value
+    ///
+    /// Returns `&str` - unique format name, used to look the format up in the registry
+    fn name(&self) -> &str;
+
+    /// Creates a streaming reader of transactions in this format
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — source, from which data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `TransactionIter` - iterator over parsed transactions
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a>;
+
+    /// Creates a streaming reader of transactions in this format with the reading settings
+    ///
+    /// Formats, which have nothing to configure, keep the default implementation,
+    /// ignoring the settings.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — source, from which data can be read
+    /// * `options` — reading settings
+    ///
+    /// # Returning value
+    ///
+    /// Returns `TransactionIter` - iterator over parsed transactions
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, _options: &ReadOptions) -> TransactionIter<'a> {
+        self.reader(reader)
+    }
+
+    /// Creates an incremental writer of transactions in this format
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — sink, into which data will be written
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Box<dyn TransactionWriter>` - writer, serializing transactions one at a time
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a>;
+
+    /// Creates an incremental writer of transactions in this format with the writing settings
+    ///
+    /// Formats, which have nothing to configure, keep the default implementation,
+    /// ignoring the settings.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — sink, into which data will be written
+    /// * `options` — writing settings
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Box<dyn TransactionWriter>` - writer, serializing transactions one at a time
+    fn writer_with_options<'a>(
+        &self,
+        writer: Box<dyn Write + 'a>,
+        _options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        self.writer(writer)
+    }
+
+    /// Checks if the beginning of the input looks like data in this format
+    ///
+    /// Used for automatic input format detection. Formats, which can not be
+    /// recognized by their content, keep the default implementation.
+    ///
+    /// # Parameters
+    ///
+    /// * `head` — first buffered bytes of the input, may be shorter than a complete record
+    ///
+    /// # Returning value
+    ///
+    /// Returns `bool` - `true` if the input is recognized as this format
+    fn detect(&self, _head: &[u8]) -> bool {
+        false
+    }
+
+    /// Splits the whole input into chunks of whole records
+    ///
+    /// Used for parallel parsing. Every chunk, preceded by its prefix, is parsed
+    /// by a separate reader. Formats, which can not be split safely, keep
+    /// the default implementation and are parsed sequentially.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` — the whole input
+    /// * `max_chunks` — maximal number of chunks
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<Vec<Chunk>>` - chunks in input order, `None` if the input can not be split
+    fn split<'d>(&self, _data: &'d [u8], _max_chunks: usize) -> Option<Vec<Chunk<'d>>> {
+        None
+    }
+}
+
+impl fmt::Debug for dyn TransactionFormat + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TransactionFormat({})", self.name())
+    }
+}
+
+impl TransactionFormat for Format {
+    fn name(&self) -> &str {
+        self.handler().name()
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        self.handler().reader(reader)
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        self.handler().reader_with_options(reader, options)
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        self.handler().writer(writer)
+    }
+
+    fn writer_with_options<'a>(
+        &self,
+        writer: Box<dyn Write + 'a>,
+        options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        self.handler().writer_with_options(writer, options)
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        self.handler().detect(head)
+    }
+
+    fn split<'d>(&self, data: &'d [u8], max_chunks: usize) -> Option<Vec<Chunk<'d>>> {
+        self.handler().split(data, max_chunks)
+    }
+}
+
+impl<T: TransactionFormat + ?Sized> TransactionFormat for &T {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        (**self).reader(reader)
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        (**self).reader_with_options(reader, options)
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        (**self).writer(writer)
+    }
+
+    fn writer_with_options<'a>(
+        &self,
+        writer: Box<dyn Write + 'a>,
+        options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        (**self).writer_with_options(writer, options)
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        (**self).detect(head)
+    }
+
+    fn split<'d>(&self, data: &'d [u8], max_chunks: usize) -> Option<Vec<Chunk<'d>>> {
+        (**self).split(data, max_chunks)
+    }
+}
+
+impl<T: TransactionFormat + ?Sized> TransactionFormat for Arc<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        (**self).reader(reader)
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        (**self).reader_with_options(reader, options)
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        (**self).writer(writer)
+    }
+
+    fn writer_with_options<'a>(
+        &self,
+        writer: Box<dyn Write + 'a>,
+        options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        (**self).writer_with_options(writer, options)
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        (**self).detect(head)
+    }
+
+    fn split<'d>(&self, data: &'d [u8], max_chunks: usize) -> Option<Vec<Chunk<'d>>> {
+        (**self).split(data, max_chunks)
+    }
+}
+
+/// Incremental writer of transactions
+///
+/// Serializes transactions one at a time, so a streaming reader can be piped
+/// into a writer without collecting all transactions in memory.
+/// `finish` must be called once after the last transaction is written.
+pub trait TransactionWriter {
+    /// Serializes a single transaction and writes it into the underlying sink
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction` — transaction to write
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(), ParseError>`:
+    /// - `Ok(())` — transaction successfully written
+    /// - `Err(ParseError)` — write or validation error
+    fn write_one(&mut self, transaction: &Transaction) -> Result<(), ParseError>;
+
+    /// Completes the output and flushes the underlying sink
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(), ParseError>`:
+    /// - `Ok(())` — output successfully completed
+    /// - `Err(ParseError)` — write error
+    fn finish(&mut self) -> Result<(), ParseError>;
+}
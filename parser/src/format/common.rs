@@ -1,9 +1,12 @@
-use crate::errors::ParseError;
-use crate::format::Format;
+use crate::errors::{Location, ParseError, ParseErrorKind};
+use crate::format::{Chunk, TransactionFormat};
+use crate::model::currency::CurrencyCode;
+use crate::model::money::Money;
 use crate::model::transaction::Transaction;
 use crate::model::transaction_status::TransactionStatus;
 use crate::model::transaction_type::TransactionType;
 use crate::parser::Parser;
+use serde_json::Value;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::PathBuf;
@@ -22,11 +25,11 @@ use std::str::FromStr;
 /// Returns `Result<(Vec<Transaction>, Vec<Transaction>), Box<dyn std::error::Error>>`:
 /// - `Ok((Vec<Transaction>, Vec<Transaction>))` — tuple of two vectors, containing transactions
 /// - `Err(Box<dyn std::error::Error>>)` — dynamically typed error
-pub fn prepare_transactions(
+pub fn prepare_transactions<F1: TransactionFormat, F2: TransactionFormat>(
     file1_path: &PathBuf,
-    format1: Format,
+    format1: F1,
     file2_path: &PathBuf,
-    format2: Format,
+    format2: F2,
 ) -> Result<(Vec<Transaction>, Vec<Transaction>), Box<dyn std::error::Error>> {
     let mut file1 = BufReader::new(File::open(file1_path)?);
     let mut file2 = BufReader::new(File::open(file2_path)?);
@@ -40,23 +43,22 @@ pub fn prepare_transactions(
 ///
 /// * `field_name` — parsed field name
 /// * `value` — parsed value 
-/// * `line_number` — file line number
+/// * `location` — location of the value in the input
 ///
 /// # Returning value
 ///
 /// Returns `Result<T, ParseError>`:
 /// - `Ok(T)` — number parsed from &str
 /// - `Err(ParseError)` — parsing error
-pub fn parse_number<T>(field_name: &str, value: &str, line_number: usize) -> Result<T, ParseError>
+pub fn parse_number<T>(field_name: &str, value: &str, location: &Location) -> Result<T, ParseError>
 where
     T: FromStr,
     T::Err: std::fmt::Display,
 {
     value.parse::<T>().map_err(|e| {
-        ParseError::Validation(format!(
-            "Invalid {} value '{}' at line {}: {}",
-            field_name, value, line_number, e
-        ))
+        ParseError::new(ParseErrorKind::InvalidNumber(value.to_string(), e.to_string()))
+            .with_field(field_name)
+            .with_location(location.clone())
     })
 }
 
@@ -64,60 +66,249 @@ where
 /// # Parameters
 ///
 /// * `value` — parsed value 
-/// * `line_number` — file line number
+/// * `location` — location of the value in the input
 ///
 /// # Returning value
 ///
 /// Returns `Result<TransactionType, ParseError>`:
 /// - `Ok(TransactionType)` — TransactionType parsed from &str
 /// - `Err(ParseError)` — parsing error
-pub fn parse_transaction_type(value: &str, line_number: usize) -> Result<TransactionType, ParseError> {
+pub fn parse_transaction_type(value: &str, location: &Location) -> Result<TransactionType, ParseError> {
     TransactionType::from_str(value)
-        .map_err(|e| ParseError::Validation(format!(
-            "Invalid TX_TYPE '{}' at line {}: {}",
-            value, line_number, e
-        )))
+        .map_err(|_| ParseError::new(ParseErrorKind::InvalidTransactionType(value.to_string()))
+            .with_field("TX_TYPE")
+            .with_location(location.clone()))
 }
 
 /// Parses &str for TransactionStatus 
 /// # Parameters
 ///
 /// * `value` — parsed value 
-/// * `line_number` — file line number
+/// * `location` — location of the value in the input
 ///
 /// # Returning value
 ///
 /// Returns `Result<TransactionStatus, ParseError>`:
 /// - `Ok(TransactionStatus)` — TransactionStatus parsed from &str
 /// - `Err(ParseError)` — parsing error
-pub fn parse_transaction_status(value: &str, line_number: usize) -> Result<TransactionStatus, ParseError> {
+pub fn parse_transaction_status(value: &str, location: &Location) -> Result<TransactionStatus, ParseError> {
     TransactionStatus::from_str(value)
-        .map_err(|e| ParseError::Validation(format!(
-            "Invalid STATUS '{}' at line {}: {}",
-            value, line_number, e
+        .map_err(|_| ParseError::new(ParseErrorKind::InvalidTransactionStatus(value.to_string()))
+            .with_field("STATUS")
+            .with_location(location.clone()))
+}
+
+/// Parses &str for currency code
+/// # Parameters
+///
+/// * `value` — parsed value
+/// * `location` — location of the value in the input
+///
+/// # Returning value
+///
+/// Returns `Result<CurrencyCode, ParseError>`:
+/// - `Ok(CurrencyCode)` — currency parsed from &str
+/// - `Err(ParseError)` — parsing error
+pub fn parse_currency(value: &str, location: &Location) -> Result<CurrencyCode, ParseError> {
+    CurrencyCode::from_str(value).map_err(|e| e.with_field("CURRENCY").with_location(location.clone()))
+}
+
+/// Converts amount to minor units of its currency for the formats, which do not store scale
+/// # Parameters
+///
+/// * `amount` — amount to convert
+///
+/// # Returning value
+///
+/// Returns `Result<i64, ParseError>`:
+/// - `Ok(i64)` — amount in minor units of the currency
+/// - `Err(ParseError)` — amount can not be represented exactly
+pub fn currency_minor_units(amount: &Money) -> Result<i64, ParseError> {
+    amount.currency_minor_units().ok_or_else(|| {
+        ParseError::new(ParseErrorKind::Validation(format!(
+            "amount {} can not be represented in minor units of {}",
+            amount, amount.currency
         )))
+        .with_field("AMOUNT")
+    })
 }
 
-/// Parses &str for description 
+/// Parses &str for description
+///
+/// The value must be enclosed in double quotes. Inside the quotes `\"`, `\\`, `\n`
+/// and `\u{..}` with a hexadecimal code point are unescaped. Legacy files did not escape
+/// descriptions, so by default unknown escapes and unescaped quotes are kept as is;
+/// in strict mode they are rejected.
 /// # Parameters
 ///
-/// * `value` — parsed value 
-/// * `line_number` — file line number
+/// * `value` — parsed value
+/// * `location` — location of the value in the input
+/// * `strict` — whether to reject ambiguous legacy input
 ///
 /// # Returning value
 ///
 /// Returns `Result<String, ParseError>`:
 /// - `Ok(String)` — description parsed from &str
 /// - `Err(ParseError)` — parsing error
-pub fn parse_description(value: &str, line_number: usize) -> Result<String, ParseError> {
-    if !value.starts_with('"') || !value.ends_with('"') {
-        return Err(ParseError::Validation(format!(
-            "DESCRIPTION must be in double quotes at line {}: {}",
-            line_number, value
-        )));
+pub fn parse_description(value: &str, location: &Location, strict: bool) -> Result<String, ParseError> {
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return Err(ParseError::new(ParseErrorKind::UnquotedDescription(value.to_string()))
+            .with_field("DESCRIPTION")
+            .with_location(location.clone()));
     }
-
     let content = &value[1..value.len() - 1];
+    let error = |offset: usize, message: String| {
+        ParseError::new(ParseErrorKind::InvalidEscape(message))
+            .with_location(Location {
+                column: location.column.map(|column| column + 1 + content[..offset].chars().count()),
+                ..Location::default()
+            })
+            .with_field("DESCRIPTION")
+            .with_location(location.clone())
+    };
+    let mut description = String::with_capacity(content.len());
+    let mut position = 0;
+    while let Some(c) = content[position..].chars().next() {
+        let offset = position;
+        position += c.len_utf8();
+        match c {
+            '\\' => {
+                let escaped = content[position..].chars().next();
+                position += escaped.map_or(0, char::len_utf8);
+                match escaped {
+                    Some('"') => description.push('"'),
+                    Some('\\') => description.push('\\'),
+                    Some('n') => description.push('\n'),
+                    Some('u') => match parse_unicode_escape(&content[position..]) {
+                        Some((unescaped, size)) => {
+                            description.push(unescaped);
+                            position += size;
+                        }
+                        None if strict => return Err(error(offset, "invalid unicode escape".to_string())),
+                        None => description.push_str("\\u"),
+                    },
+                    Some(other) if strict => return Err(error(offset, format!("unknown escape \\{}", other))),
+                    Some(other) => {
+                        description.push('\\');
+                        description.push(other);
+                    }
+                    None if strict => return Err(error(offset, "closing quote is escaped".to_string())),
+                    None => description.push('\\'),
+                }
+            }
+            '"' if strict => return Err(error(offset, "unescaped quote".to_string())),
+            c => description.push(c),
+        }
+    }
+    Ok(description)
+}
 
-    Ok(content.to_string())
-}
\ No newline at end of file
+/// Parses the `{..}` part of a `\u{..}` escape
+///
+/// # Returning value
+///
+/// Returns `Option<(char, usize)>` - unescaped char and size of the part in bytes
+fn parse_unicode_escape(rest: &str) -> Option<(char, usize)> {
+    let digits = rest.strip_prefix('{')?;
+    let end = digits.find('}')?;
+    let digits = &digits[..end];
+    if digits.is_empty() || digits.len() > 6 {
+        return None;
+    }
+    let code_point = u32::from_str_radix(digits, 16).ok()?;
+    Some((char::from_u32(code_point)?, end + 2))
+}
+
+/// Escapes description for a double quoted value of YPBankText format
+///
+/// Quotes, backslashes and line feeds are escaped with a backslash,
+/// other control characters with `\u{..}`, so the value always fits on one line.
+/// # Parameters
+///
+/// * `value` — description to escape
+///
+/// # Returning value
+///
+/// Returns `String` - escaped description without the enclosing quotes
+pub fn escape_description(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", u32::from(c))),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Checks requirements to a transaction, deserialized as a whole
+/// # Parameters
+///
+/// * `transaction` — deserialized transaction
+/// * `location` — location of the transaction in the input
+///
+/// # Returning value
+///
+/// Returns `Result<Transaction, ParseError>`:
+/// - `Ok(Transaction)` — valid transaction
+/// - `Err(ParseError)` — validation error
+pub fn check_transaction(transaction: Transaction, location: &Location) -> Result<Transaction, ParseError> {
+    transaction.validate().map_err(|e| e.with_location(location.clone()))?;
+    Ok(transaction)
+}
+
+/// Moves an amount, written as a bare number of minor units, into the default currency of the reader
+/// # Parameters
+///
+/// * `transaction` — transaction, deserialized from `value`
+/// * `value` — JSON object of the transaction
+/// * `default_currency` — currency of amounts without a currency
+pub fn apply_default_currency(transaction: &mut Transaction, value: &Value, default_currency: CurrencyCode) {
+    if value.get("amount").is_some_and(Value::is_number) {
+        transaction.amount = Money::new(transaction.amount.minor_units, default_currency);
+    }
+}
+
+/// Groups records into at most `max_chunks` chunks of similar size
+///
+/// # Parameters
+///
+/// * `data` — the whole input
+/// * `prefix` — bytes, which must be read before every chunk
+/// * `starts` — byte and line offsets of record starts in input order,
+///   the first one is the beginning of the records
+/// * `max_chunks` — maximal number of chunks
+///
+/// # Returning value
+///
+/// Returns `Vec<Chunk>` - chunks, the last one ends at the end of `data`
+pub fn split_at_records<'a>(
+    data: &'a [u8],
+    prefix: &'a [u8],
+    starts: &[(usize, usize)],
+    max_chunks: usize,
+) -> Vec<Chunk<'a>> {
+    let Some(&first) = starts.first() else {
+        return Vec::new();
+    };
+    let target_size = (data.len() - first.0).div_ceil(max_chunks.max(1)).max(1);
+    let chunk = |(start, line_offset): (usize, usize), end: usize| Chunk {
+        prefix,
+        data: &data[start..end],
+        byte_offset: start as u64,
+        line_offset,
+    };
+    let mut chunks = Vec::new();
+    let mut chunk_start = first;
+    for &start in &starts[1..] {
+        if start.0 - chunk_start.0 >= target_size {
+            chunks.push(chunk(chunk_start, start.0));
+            chunk_start = start;
+        }
+    }
+    chunks.push(chunk(chunk_start, data.len()));
+    chunks
+}
@@ -0,0 +1,552 @@
+use crate::errors::{Location, ParseError, ParseErrorKind};
+use crate::format::common::{parse_currency, parse_number, parse_transaction_status};
+use crate::format::yp_bank_csv_format::CsvDialect;
+use crate::format::{ReadOptions, TransactionFormat, TransactionIter, TransactionWriter};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::money::Money;
+use crate::model::transaction::Transaction;
+use crate::model::transaction_status::TransactionStatus;
+use crate::model::transaction_type::TransactionType;
+use chrono::{NaiveDate, NaiveDateTime};
+use csv::{ReaderBuilder, StringRecord};
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Name of the mapped CSV format
+pub const MAPPING_FORMAT_NAME: &str = "mapping";
+
+/// Description of a bank CSV export, which maps its columns to transaction fields
+///
+/// Loaded from TOML or YAML, e.g.:
+///
+/// ```toml
+/// delimiter = ";"
+/// date_formats = ["%d.%m.%Y"]
+/// decimal_separator = ","
+/// currency = "EUR"
+/// user_id = 501
+///
+/// [columns]
+/// date = "Date"
+/// debit = "Debit"
+/// credit = "Credit"
+/// description = "Payee"
+/// ```
+///
+/// A debit becomes a withdrawal from `user_id`, a credit becomes a deposit to `user_id`.
+/// With a single signed `amount` column negative amounts are withdrawals.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CsvMapping {
+    /// Field delimiter
+    #[serde(default = "CsvMapping::default_delimiter")]
+    pub delimiter: char,
+    /// Quote character
+    #[serde(default = "CsvMapping::default_quote")]
+    pub quote: char,
+    /// Names of the columns in the header line
+    pub columns: ColumnMapping,
+    /// `chrono` formats of dates and date-times, tried in order; dates are taken at midnight UTC
+    #[serde(default = "CsvMapping::default_date_formats")]
+    pub date_formats: Vec<String>,
+    /// Separator of the fractional part of amounts
+    #[serde(default = "CsvMapping::default_decimal_separator")]
+    pub decimal_separator: char,
+    /// Separator of thousands in amounts, removed before parsing
+    #[serde(default)]
+    pub thousands_separator: Option<char>,
+    /// Currency of amounts without the currency column, the default currency of `ReadOptions` if absent
+    #[serde(default)]
+    pub currency: Option<CurrencyCode>,
+    /// Status of transactions without the status column
+    #[serde(default = "CsvMapping::default_status")]
+    pub status: TransactionStatus,
+    /// Owner of the account, deposits are made to this user and withdrawals from this user
+    pub user_id: u64,
+    /// ID of the first transaction without the transaction ID column, the following ones are numbered in order
+    #[serde(default = "CsvMapping::default_first_tx_id")]
+    pub first_tx_id: u64,
+}
+
+/// Names of the columns, from which transaction fields are taken
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnMapping {
+    /// Transaction ID
+    #[serde(default)]
+    pub tx_id: Option<String>,
+    /// Date or date-time of the transaction
+    pub date: String,
+    /// Signed amount, negative for withdrawals
+    #[serde(default)]
+    pub amount: Option<String>,
+    /// Amount of withdrawals
+    #[serde(default)]
+    pub debit: Option<String>,
+    /// Amount of deposits
+    #[serde(default)]
+    pub credit: Option<String>,
+    /// Currency code
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Transaction status in YPBank notation
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Description, e.g. the payee
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ColumnMapping {
+    /// # Returning value
+    ///
+    /// Returns `Vec<String>` - names of all mapped columns
+    pub fn names(&self) -> Vec<String> {
+        [
+            &self.tx_id,
+            &Some(self.date.clone()),
+            &self.amount,
+            &self.debit,
+            &self.credit,
+            &self.currency,
+            &self.status,
+            &self.description,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+    }
+}
+
+impl CsvMapping {
+    fn default_delimiter() -> char {
+        ','
+    }
+
+    fn default_quote() -> char {
+        '"'
+    }
+
+    fn default_date_formats() -> Vec<String> {
+        vec!["%Y-%m-%d".to_string(), "%Y-%m-%d %H:%M:%S".to_string()]
+    }
+
+    fn default_decimal_separator() -> char {
+        '.'
+    }
+
+    fn default_status() -> TransactionStatus {
+        TransactionStatus::Success
+    }
+
+    fn default_first_tx_id() -> u64 {
+        1
+    }
+
+    /// Loads mapping from a TOML or YAML file, chosen by the `.yaml` or `.yml` extension
+    ///
+    /// # Parameters
+    ///
+    /// * `path` — path to the mapping file
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<CsvMapping, ParseError>`:
+    /// - `Ok(CsvMapping)` — valid mapping
+    /// - `Err(ParseError)` — file can not be read or the mapping is invalid
+    pub fn load(path: &Path) -> Result<Self, ParseError> {
+        let content = std::fs::read_to_string(path)?;
+        let is_yaml = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml"));
+        if is_yaml {
+            Self::from_yaml(&content)
+        } else {
+            Self::from_toml(&content)
+        }
+    }
+
+    /// Parses mapping in TOML
+    ///
+    /// # Parameters
+    ///
+    /// * `content` — mapping in TOML
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<CsvMapping, ParseError>`:
+    /// - `Ok(CsvMapping)` — valid mapping
+    /// - `Err(ParseError)` — syntax error or invalid mapping
+    pub fn from_toml(content: &str) -> Result<Self, ParseError> {
+        let mapping: Self = toml::from_str(content).map_err(|e| ParseErrorKind::InvalidMapping(e.to_string()))?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    /// Parses mapping in YAML
+    ///
+    /// # Parameters
+    ///
+    /// * `content` — mapping in YAML
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<CsvMapping, ParseError>`:
+    /// - `Ok(CsvMapping)` — valid mapping
+    /// - `Err(ParseError)` — syntax error or invalid mapping
+    pub fn from_yaml(content: &str) -> Result<Self, ParseError> {
+        let mapping: Self =
+            serde_yaml::from_str(content).map_err(|e| ParseErrorKind::InvalidMapping(e.to_string()))?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `CsvDialect` - delimiter and quote of the mapped data, e.g. to render errors against it
+    pub fn dialect(&self) -> CsvDialect {
+        CsvDialect {
+            delimiter: self.delimiter as u8,
+            quote: self.quote as u8,
+            ..CsvDialect::default()
+        }
+    }
+
+    /// Checks if the mapping is consistent
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(), ParseError>`:
+    /// - `Ok(())` — mapping can be used for parsing
+    /// - `Err(ParseError)` — description of the problem
+    pub fn validate(&self) -> Result<(), ParseError> {
+        let invalid = |message: &str| Err(ParseErrorKind::InvalidMapping(message.to_string()).into());
+        if !self.delimiter.is_ascii() || !self.quote.is_ascii() {
+            return invalid("delimiter and quote must be ASCII characters");
+        }
+        let columns = &self.columns;
+        match (&columns.amount, &columns.debit, &columns.credit) {
+            (Some(_), None, None) | (None, Some(_), _) | (None, _, Some(_)) => {}
+            (None, None, None) => return invalid("either amount or debit and credit columns must be set"),
+            (Some(_), _, _) => return invalid("amount column can not be combined with debit and credit columns"),
+        }
+        if self.date_formats.is_empty() {
+            return invalid("at least one date format must be set");
+        }
+        Ok(())
+    }
+
+    /// Parses a date or date-time with the first matching format
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<u64>` - milliseconds since the Unix epoch in UTC
+    fn parse_timestamp(&self, value: &str) -> Option<u64> {
+        self.date_formats.iter().find_map(|format| {
+            let date_time = NaiveDateTime::parse_from_str(value, format)
+                .ok()
+                .or_else(|| NaiveDate::parse_from_str(value, format).ok()?.and_hms_opt(0, 0, 0))?;
+            u64::try_from(date_time.and_utc().timestamp_millis()).ok()
+        })
+    }
+
+    /// Parses a decimal amount into minor units with the given scale
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<i64, String>`:
+    /// - `Ok(i64)` — signed amount in minor units
+    /// - `Err(String)` — description of the problem
+    fn parse_amount(&self, value: &str, scale: u8) -> Result<i64, String> {
+        let normalized: String = value
+            .chars()
+            .filter(|&c| Some(c) != self.thousands_separator)
+            .map(|c| if c == self.decimal_separator { '.' } else { c })
+            .collect();
+        let (negative, digits) = match normalized.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, normalized.strip_prefix('+').unwrap_or(&normalized)),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if integer.is_empty() && fraction.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+            return Err("not a decimal number".to_string());
+        }
+        if fraction.len() > usize::from(scale) {
+            return Err(format!("more than {} digits after the decimal separator", scale));
+        }
+        let padded = format!("{}{:0<width$}", integer, fraction, width = usize::from(scale));
+        let minor_units: i64 = padded.parse().map_err(|e: std::num::ParseIntError| e.to_string())?;
+        Ok(if negative { -minor_units } else { minor_units })
+    }
+}
+
+impl TransactionFormat for CsvMapping {
+    fn name(&self) -> &str {
+        MAPPING_FORMAT_NAME
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        Box::new(CsvMappingReader::new(reader, self.clone()))
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        let mapping = CsvMapping {
+            currency: self.currency.or(Some(options.default_currency)),
+            ..self.clone()
+        };
+        Box::new(CsvMappingReader::new(reader, mapping))
+    }
+
+    fn writer<'a>(&self, _writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(CsvMappingWriter)
+    }
+}
+
+/// Writer returned for `CsvMapping`, rejecting every write
+struct CsvMappingWriter;
+
+impl TransactionWriter for CsvMappingWriter {
+    fn write_one(&mut self, _transaction: &Transaction) -> Result<(), ParseError> {
+        Err(ParseErrorKind::ReadOnlyFormat(MAPPING_FORMAT_NAME.to_string()).into())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        Err(ParseErrorKind::ReadOnlyFormat(MAPPING_FORMAT_NAME.to_string()).into())
+    }
+}
+
+/// Position and name of a mapped column
+type MappedColumn = Option<(usize, String)>;
+
+/// Positions of the mapped columns in a record
+#[derive(Debug)]
+struct MappedColumns {
+    tx_id: MappedColumn,
+    date: MappedColumn,
+    amount: MappedColumn,
+    debit: MappedColumn,
+    credit: MappedColumn,
+    currency: MappedColumn,
+    status: MappedColumn,
+    description: MappedColumn,
+}
+
+/// Streaming reader of transactions from a bank CSV export, described by `CsvMapping`
+///
+/// Finds the mapped columns in the header line on the first call to `next`, then yields
+/// a transaction for every CSV record. An invalid record is reported as an error
+/// and skipped, iteration stops after invalid headers or an I/O error.
+pub struct CsvMappingReader<R: Read> {
+    csv_reader: csv::Reader<R>,
+    mapping: CsvMapping,
+    record: StringRecord,
+    record_index: usize,
+    columns: Option<MappedColumns>,
+    finished: bool,
+}
+
+impl<R: Read> CsvMappingReader<R> {
+    /// Creates a new reader
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which CSV data can be read
+    /// * `mapping` — description of the columns
+    pub fn new(reader: R, mapping: CsvMapping) -> Self {
+        let csv_reader = ReaderBuilder::new()
+            .has_headers(true)
+            .trim(csv::Trim::All)
+            .flexible(false)
+            .delimiter(mapping.delimiter as u8)
+            .quote(mapping.quote as u8)
+            .from_reader(reader);
+        Self {
+            csv_reader,
+            mapping,
+            record: StringRecord::new(),
+            record_index: 0,
+            columns: None,
+            finished: false,
+        }
+    }
+
+    /// Finds the mapped columns in the header line
+    fn read_columns(&mut self) -> Result<MappedColumns, ParseError> {
+        let headers = self.csv_reader.headers()?;
+        let actual_fields: Vec<String> = headers.iter().map(|s| s.trim().to_string()).collect();
+        let mapping = &self.mapping.columns;
+        let invalid = || {
+            ParseError::new(ParseErrorKind::InvalidHeader(
+                actual_fields.clone(),
+                mapping.names(),
+            ))
+            .with_location(Location {
+                line: headers.position().map(|p| p.line() as usize),
+                byte_offset: headers.position().map(|p| p.byte()),
+                ..Location::default()
+            })
+        };
+        let find = |name: &Option<String>| -> Result<MappedColumn, ParseError> {
+            let Some(name) = name else {
+                return Ok(None);
+            };
+            let position = actual_fields.iter().position(|field| field == name).ok_or_else(invalid)?;
+            Ok(Some((position, name.clone())))
+        };
+        Ok(MappedColumns {
+            tx_id: find(&mapping.tx_id)?,
+            date: find(&Some(mapping.date.clone()))?,
+            amount: find(&mapping.amount)?,
+            debit: find(&mapping.debit)?,
+            credit: find(&mapping.credit)?,
+            currency: find(&mapping.currency)?,
+            status: find(&mapping.status)?,
+            description: find(&mapping.description)?,
+        })
+    }
+
+    /// Reads the next non-empty CSV record and maps it
+    fn read_record(&mut self, record_index: usize) -> Result<Option<Transaction>, ParseError> {
+        while self.csv_reader.read_record(&mut self.record)? {
+            if self.record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            let location = Location {
+                line: self.record.position().map(|p| p.line() as usize),
+                byte_offset: self.record.position().map(|p| p.byte()),
+                ..Location::default()
+            };
+            let columns = self.columns.as_ref().expect("columns are read before records");
+            return map_record(&self.mapping, columns, &self.record, &location, record_index).map(Some);
+        }
+        Ok(None)
+    }
+}
+
+/// Returns the value of the mapped column and location of the value
+fn column_value<'r>(
+    record: &'r StringRecord,
+    column: &MappedColumn,
+    location: &Location,
+) -> Option<(&'r str, Location)> {
+    let (position, name) = column.as_ref()?;
+    let location = Location {
+        field: Some(name.clone()),
+        ..location.clone()
+    };
+    Some((record.get(*position).unwrap_or_default(), location))
+}
+
+/// Converts a record into a transaction according to the mapping
+fn map_record(
+    mapping: &CsvMapping,
+    columns: &MappedColumns,
+    record: &StringRecord,
+    location: &Location,
+    record_index: usize,
+) -> Result<Transaction, ParseError> {
+    let tx_id = match column_value(record, &columns.tx_id, location) {
+        Some((value, location)) => parse_number("TX_ID", value, &location)?,
+        None => mapping.first_tx_id + record_index as u64,
+    };
+    let (date, date_location) = column_value(record, &columns.date, location).unwrap_or_default();
+    let timestamp = mapping.parse_timestamp(date).ok_or_else(|| {
+        ParseError::new(ParseErrorKind::InvalidDate(date.to_string(), mapping.date_formats.clone()))
+            .with_location(date_location)
+    })?;
+    let currency = match column_value(record, &columns.currency, location) {
+        Some((value, location)) => parse_currency(value, &location)?,
+        None => mapping.currency.unwrap_or(DEFAULT_CURRENCY),
+    };
+    let parse_amount = |(value, location): (&str, Location)| {
+        mapping.parse_amount(value, currency.minor_unit()).map_err(|reason| {
+            ParseError::new(ParseErrorKind::InvalidNumber(value.to_string(), reason)).with_location(location)
+        })
+    };
+    let non_empty = |column: &MappedColumn| column_value(record, column, location).filter(|(value, _)| !value.is_empty());
+    let amount = match (non_empty(&columns.amount), non_empty(&columns.debit), non_empty(&columns.credit)) {
+        (Some(amount), _, _) => parse_amount(amount)?,
+        (None, Some(debit), None) => -parse_amount(debit)?.abs(),
+        (None, None, Some(credit)) => parse_amount(credit)?.abs(),
+        (None, Some(_), Some(_)) => {
+            return Err(ParseError::new(ParseErrorKind::Validation(
+                "both debit and credit are set".to_string(),
+            ))
+            .with_location(location.clone()))
+        }
+        (None, None, None) => {
+            let names = [&columns.amount, &columns.debit, &columns.credit]
+                .into_iter()
+                .flatten()
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(ParseError::new(ParseErrorKind::MissingField(names)).with_location(location.clone()));
+        }
+    };
+    if amount == 0 {
+        return Err(ParseError::new(ParseErrorKind::InvalidAmount(amount)).with_location(location.clone()));
+    }
+    let (tx_type, from_user_id, to_user_id) = if amount < 0 {
+        (TransactionType::Withdrawal, mapping.user_id, 0)
+    } else {
+        (TransactionType::Deposit, 0, mapping.user_id)
+    };
+    let status = match column_value(record, &columns.status, location) {
+        Some((value, location)) => parse_transaction_status(value, &location)?,
+        None => mapping.status,
+    };
+    let description = column_value(record, &columns.description, location)
+        .map(|(value, _)| value.to_string())
+        .unwrap_or_default();
+    let transaction = Transaction {
+        tx_id,
+        tx_type,
+        from_user_id,
+        to_user_id,
+        amount: Money::new(amount.abs(), currency),
+        timestamp,
+        status,
+        description,
+    };
+    transaction.validate().map_err(|e| e.with_location(location.clone()))?;
+    Ok(transaction)
+}
+
+impl<R: Read> Iterator for CsvMappingReader<R> {
+    type Item = Result<Transaction, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if self.columns.is_none() {
+            match self.read_columns() {
+                Ok(columns) => self.columns = Some(columns),
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        let record_index = self.record_index;
+        self.record_index += 1;
+        match self.read_record(record_index) {
+            Ok(Some(transaction)) => Some(Ok(transaction)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = !e.is_recoverable();
+                Some(Err(e.with_location(Location {
+                    record_index: Some(record_index),
+                    ..Location::default()
+                })))
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use crate::errors::{Location, ParseError};
+use crate::format::common::{apply_default_currency, check_transaction};
+use crate::format::{ReadOptions, TransactionFormat, TransactionIter, TransactionWriter};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::transaction::Transaction;
+use crate::validation::check_amount;
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{BufReader, Read, Write};
+
+/// Reading and writing data in JSON format
+///
+/// Transactions are stored as a single JSON array of objects.
+pub struct JsonParser;
+impl JsonParser {
+    /// Parses data in JSON format from different sources.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which JSON data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Vec<Transaction>, ParseError>`:
+    /// - `Ok(Vec<Transaction>)` — successful parsing, contains transaction vector
+    /// - `Err(ParseError)` — parsing error (I/O, JSON, validation etc.)
+    pub fn parse<R: Read>(reader: R) -> Result<Vec<Transaction>, ParseError> {
+        Self::iter(reader).collect()
+    }
+
+    /// Creates a reader over data in JSON format.
+    ///
+    /// The array is read as a whole on the first call to `next`,
+    /// its elements are converted into transactions lazily.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which JSON data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `JsonReader<R>` - iterator over parsed transactions
+    pub fn iter<R: Read>(reader: R) -> JsonReader<R> {
+        JsonReader::new(reader)
+    }
+
+    /// Writes transaction vector into chosen sink in JSON format.
+    ///
+    /// # Parameters
+    ///
+    /// * `transactions` — transaction slice to write
+    /// * `writer` — any type, implementing `std::io::Write`, into which JSON data will be written
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(), ParseError>`:
+    /// - `Ok(())` — all transactions successfully written
+    /// - `Err(ParseError)` — write or validation error
+    pub fn write<W: Write>(transactions: &[Transaction], writer: &mut W) -> Result<(), ParseError> {
+        let mut json_writer = Self::writer(writer);
+        for transaction in transactions {
+            json_writer.write_one(transaction)?;
+        }
+        json_writer.finish()
+    }
+
+    /// Creates an incremental writer of transactions in JSON format.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which JSON data will be written
+    ///
+    /// # Returning value
+    ///
+    /// Returns `JsonWriter<W>` - writer, serializing transactions one at a time
+    pub fn writer<W: Write>(writer: W) -> JsonWriter<W> {
+        JsonWriter::new(writer)
+    }
+}
+
+impl TransactionFormat for JsonParser {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        Box::new(JsonParser::iter(reader))
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        Box::new(JsonParser::iter(reader).with_default_currency(options.default_currency))
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(JsonParser::writer(writer))
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        String::from_utf8_lossy(head)
+            .trim_start_matches('\u{feff}')
+            .trim_start()
+            .starts_with('[')
+    }
+}
+
+/// Incremental writer of transactions in JSON format
+///
+/// Opening bracket is written before the first record, closing one on `finish`,
+/// every record is placed on its own line.
+pub struct JsonWriter<W: Write> {
+    writer: W,
+    records_written: usize,
+    closed: bool,
+}
+
+impl<W: Write> JsonWriter<W> {
+    /// Creates a new writer
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which JSON data will be written
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            records_written: 0,
+            closed: false,
+        }
+    }
+}
+
+impl<W: Write> TransactionWriter for JsonWriter<W> {
+    fn write_one(&mut self, transaction: &Transaction) -> Result<(), ParseError> {
+        check_amount(transaction)?;
+        let separator = if self.records_written == 0 { "[\n  " } else { ",\n  " };
+        self.writer.write_all(separator.as_bytes())?;
+        serde_json::to_writer(&mut self.writer, transaction)?;
+        self.records_written += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        if !self.closed {
+            let closing = if self.records_written == 0 { "[]\n" } else { "\n]\n" };
+            self.writer.write_all(closing.as_bytes())?;
+            self.closed = true;
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Reader of transactions in JSON format
+///
+/// Reads the whole array on the first call to `next` and then yields
+/// a transaction for every element. An invalid element is reported as an error
+/// and skipped, iteration stops after a JSON syntax or I/O error.
+pub struct JsonReader<R: Read> {
+    reader: Option<R>,
+    values: std::vec::IntoIter<Value>,
+    record_index: usize,
+    default_currency: CurrencyCode,
+    finished: bool,
+}
+
+impl<R: Read> JsonReader<R> {
+    /// Creates a new reader
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which JSON data can be read
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Some(reader),
+            values: Vec::new().into_iter(),
+            record_index: 0,
+            default_currency: DEFAULT_CURRENCY,
+            finished: false,
+        }
+    }
+
+    /// Chooses currency of amounts, written as bare numbers of minor units
+    ///
+    /// # Parameters
+    ///
+    /// * `currency` — currency of amounts, `DEFAULT_CURRENCY` unless chosen
+    ///
+    /// # Returning value
+    ///
+    /// Returns `JsonReader<R>` - the reader with the default currency
+    pub fn with_default_currency(mut self, currency: CurrencyCode) -> Self {
+        self.default_currency = currency;
+        self
+    }
+}
+
+impl<R: Read> Iterator for JsonReader<R> {
+    type Item = Result<Transaction, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if let Some(reader) = self.reader.take() {
+            match serde_json::from_reader::<_, Vec<Value>>(BufReader::new(reader)) {
+                Ok(values) => self.values = values.into_iter(),
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+        let Some(value) = self.values.next() else {
+            self.finished = true;
+            return None;
+        };
+        let location = Location {
+            record_index: Some(self.record_index),
+            ..Location::default()
+        };
+        self.record_index += 1;
+        Some(
+            Transaction::deserialize(&value)
+                .map_err(|e| ParseError::from(e).with_location(location.clone()))
+                .and_then(|mut transaction| {
+                    apply_default_currency(&mut transaction, &value, self.default_currency);
+                    check_transaction(transaction, &location)
+                }),
+        )
+    }
+}
@@ -0,0 +1,229 @@
+use crate::errors::{Location, ParseError, ParseErrorKind};
+use crate::format::common::{apply_default_currency, check_transaction};
+use crate::format::{ReadOptions, TransactionFormat, TransactionIter, TransactionWriter};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::transaction::Transaction;
+use crate::validation::check_amount;
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Lines, Read, Write};
+
+/// Reading and writing data in JSON Lines format
+///
+/// Every transaction is stored as a JSON object on its own line.
+pub struct JsonLinesParser;
+impl JsonLinesParser {
+    /// Parses data in JSON Lines format from different sources.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which JSON Lines data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Vec<Transaction>, ParseError>`:
+    /// - `Ok(Vec<Transaction>)` — successful parsing, contains transaction vector
+    /// - `Err(ParseError)` — parsing error (I/O, JSON, validation etc.)
+    pub fn parse<R: Read>(reader: R) -> Result<Vec<Transaction>, ParseError> {
+        Self::iter(reader).collect()
+    }
+
+    /// Creates a streaming reader over data in JSON Lines format.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which JSON Lines data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `JsonLinesReader<R>` - iterator over parsed transactions
+    pub fn iter<R: Read>(reader: R) -> JsonLinesReader<R> {
+        JsonLinesReader::new(reader)
+    }
+
+    /// Writes transaction vector into chosen sink in JSON Lines format.
+    ///
+    /// # Parameters
+    ///
+    /// * `transactions` — transaction slice to write
+    /// * `writer` — any type, implementing `std::io::Write`, into which JSON Lines data will be written
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(), ParseError>`:
+    /// - `Ok(())` — all transactions successfully written
+    /// - `Err(ParseError)` — write or validation error
+    pub fn write<W: Write>(transactions: &[Transaction], writer: &mut W) -> Result<(), ParseError> {
+        let mut json_lines_writer = Self::writer(writer);
+        for transaction in transactions {
+            json_lines_writer.write_one(transaction)?;
+        }
+        json_lines_writer.finish()
+    }
+
+    /// Creates an incremental writer of transactions in JSON Lines format.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which JSON Lines data will be written
+    ///
+    /// # Returning value
+    ///
+    /// Returns `JsonLinesWriter<W>` - writer, serializing transactions one at a time
+    pub fn writer<W: Write>(writer: W) -> JsonLinesWriter<W> {
+        JsonLinesWriter::new(writer)
+    }
+}
+
+impl TransactionFormat for JsonLinesParser {
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        Box::new(JsonLinesParser::iter(reader))
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        Box::new(JsonLinesParser::iter(reader).with_default_currency(options.default_currency))
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(JsonLinesParser::writer(writer))
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        String::from_utf8_lossy(head)
+            .trim_start_matches('\u{feff}')
+            .trim_start()
+            .starts_with('{')
+    }
+}
+
+/// Incremental writer of transactions in JSON Lines format
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// Creates a new writer
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which JSON Lines data will be written
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write> TransactionWriter for JsonLinesWriter<W> {
+    fn write_one(&mut self, transaction: &Transaction) -> Result<(), ParseError> {
+        check_amount(transaction)?;
+        serde_json::to_writer(&mut self.writer, transaction)?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Streaming reader of transactions in JSON Lines format
+///
+/// Reads the source line by line and yields a transaction for every
+/// non-empty line. A line with invalid JSON or invalid transaction is reported
+/// as an error and skipped, iteration stops after an I/O error.
+pub struct JsonLinesReader<R: Read> {
+    lines: Lines<BufReader<R>>,
+    line_number: usize,
+    record_index: usize,
+    default_currency: CurrencyCode,
+    finished: bool,
+}
+
+impl<R: Read> JsonLinesReader<R> {
+    /// Creates a new reader
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which JSON Lines data can be read
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: BufReader::new(reader).lines(),
+            line_number: 0,
+            record_index: 0,
+            default_currency: DEFAULT_CURRENCY,
+            finished: false,
+        }
+    }
+
+    /// Chooses currency of amounts, written as bare numbers of minor units
+    ///
+    /// # Parameters
+    ///
+    /// * `currency` — currency of amounts, `DEFAULT_CURRENCY` unless chosen
+    ///
+    /// # Returning value
+    ///
+    /// Returns `JsonLinesReader<R>` - the reader with the default currency
+    pub fn with_default_currency(mut self, currency: CurrencyCode) -> Self {
+        self.default_currency = currency;
+        self
+    }
+
+    /// Reads the next non-empty line and parses it
+    fn read_record(&mut self) -> Result<Option<Transaction>, ParseError> {
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let location = Location {
+                line: Some(self.line_number),
+                ..Location::default()
+            };
+            let value: Value = serde_json::from_str(&line).map_err(|e| {
+                let column = e.column();
+                ParseError::new(ParseErrorKind::JsonLine(e)).with_location(Location {
+                    line: Some(self.line_number),
+                    column: Some(column),
+                    ..Location::default()
+                })
+            })?;
+            let mut transaction = Transaction::deserialize(&value)
+                .map_err(|e| ParseError::from(e).with_location(location.clone()))?;
+            apply_default_currency(&mut transaction, &value, self.default_currency);
+            return check_transaction(transaction, &location).map(Some);
+        }
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for JsonLinesReader<R> {
+    type Item = Result<Transaction, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let record_index = self.record_index;
+        self.record_index += 1;
+        match self.read_record() {
+            Ok(Some(transaction)) => Some(Ok(transaction)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = !e.is_recoverable();
+                Some(Err(e.with_location(Location {
+                    record_index: Some(record_index),
+                    ..Location::default()
+                })))
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::errors::{ParseError, ParseErrorKind};
+use crate::format::{Format, ReadOptions, TransactionFormat, TransactionIter, TransactionWriter};
+use crate::model::transaction::Transaction;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Name of the pseudo-format, which detects the actual input format
+pub const AUTO_FORMAT_NAME: &str = "auto";
+
+/// Registry of transaction formats available by name
+///
+/// Format names are matched case-insensitively. Registering a format
+/// with an already known name replaces the previous one.
+pub struct FormatRegistry {
+    formats: Vec<Arc<dyn TransactionFormat>>,
+}
+
+impl FormatRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self { formats: Vec::new() }
+    }
+
+    /// Creates a registry containing all built-in formats
+    pub fn with_builtin_formats() -> Self {
+        let mut registry = Self::new();
+        for format in [Format::Text, Format::Csv, Format::Binary, Format::Json, Format::JsonLines] {
+            registry.register(format);
+        }
+        registry
+    }
+
+    /// Adds a format to the registry
+    ///
+    /// # Parameters
+    ///
+    /// * `format` — format implementation
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<Arc<dyn TransactionFormat>>` - previously registered format with the same name
+    pub fn register<F: TransactionFormat + 'static>(&mut self, format: F) -> Option<Arc<dyn TransactionFormat>> {
+        let format: Arc<dyn TransactionFormat> = Arc::new(format);
+        match self.position(format.name()) {
+            Some(index) => Some(std::mem::replace(&mut self.formats[index], format)),
+            None => {
+                self.formats.push(format);
+                None
+            }
+        }
+    }
+
+    /// Looks a format up by its name
+    ///
+    /// # Parameters
+    ///
+    /// * `name` — format name
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Arc<dyn TransactionFormat>, ParseError>`:
+    /// - `Ok(Arc<dyn TransactionFormat>)` — found format
+    /// - `Err(ParseError)` — format with such name is not registered
+    pub fn get(&self, name: &str) -> Result<Arc<dyn TransactionFormat>, ParseError> {
+        self.position(name)
+            .map(|index| Arc::clone(&self.formats[index]))
+            .ok_or_else(|| ParseErrorKind::UnknownFormat(name.to_string(), self.names()).into())
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `Vec<String>` - names of all registered formats in registration order
+    pub fn names(&self) -> Vec<String> {
+        self.formats.iter().map(|format| format.name().to_string()).collect()
+    }
+
+    /// Detects format of the input among registered formats
+    ///
+    /// # Parameters
+    ///
+    /// * `head` — first buffered bytes of the input
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<Arc<dyn TransactionFormat>>` - the first registered format, which recognizes the input
+    pub fn detect(&self, head: &[u8]) -> Option<Arc<dyn TransactionFormat>> {
+        self.formats.iter().find(|format| format.detect(head)).cloned()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.formats
+            .iter()
+            .position(|format| format.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        Self::with_builtin_formats()
+    }
+}
+
+/// Process-wide registry, initialized with built-in formats
+fn global() -> &'static RwLock<FormatRegistry> {
+    static REGISTRY: OnceLock<RwLock<FormatRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(FormatRegistry::with_builtin_formats()))
+}
+
+/// Adds a format to the process-wide registry
+///
+/// # Parameters
+///
+/// * `format` — format implementation
+///
+/// # Returning value
+///
+/// Returns `Option<Arc<dyn TransactionFormat>>` - previously registered format with the same name
+pub fn register_format<F: TransactionFormat + 'static>(format: F) -> Option<Arc<dyn TransactionFormat>> {
+    global().write().unwrap_or_else(|e| e.into_inner()).register(format)
+}
+
+/// Looks a format up by its name in the process-wide registry
+///
+/// Can be used as a `clap` value parser for format arguments.
+///
+/// # Parameters
+///
+/// * `name` — format name
+///
+/// # Returning value
+///
+/// Returns `Result<Arc<dyn TransactionFormat>, ParseError>`:
+/// - `Ok(Arc<dyn TransactionFormat>)` — found format
+/// - `Err(ParseError)` — format with such name is not registered
+pub fn find_format(name: &str) -> Result<Arc<dyn TransactionFormat>, ParseError> {
+    global().read().unwrap_or_else(|e| e.into_inner()).get(name)
+}
+
+/// Looks an input format up by its name in the process-wide registry
+///
+/// Besides registered formats accepts `auto`, which detects the actual format
+/// when reading. Can be used as a `clap` value parser for input format arguments.
+///
+/// # Parameters
+///
+/// * `name` — format name or `auto`
+///
+/// # Returning value
+///
+/// Returns `Result<Arc<dyn TransactionFormat>, ParseError>`:
+/// - `Ok(Arc<dyn TransactionFormat>)` — found format
+/// - `Err(ParseError)` — format with such name is not registered
+pub fn find_input_format(name: &str) -> Result<Arc<dyn TransactionFormat>, ParseError> {
+    if name.eq_ignore_ascii_case(AUTO_FORMAT_NAME) {
+        Ok(Arc::new(AutoFormat))
+    } else {
+        find_format(name)
+    }
+}
+
+/// Detects format of the input among formats in the process-wide registry
+///
+/// # Parameters
+///
+/// * `head` — first buffered bytes of the input
+///
+/// # Returning value
+///
+/// Returns `Option<Arc<dyn TransactionFormat>>` - detected format
+pub fn detect_format(head: &[u8]) -> Option<Arc<dyn TransactionFormat>> {
+    global().read().unwrap_or_else(|e| e.into_inner()).detect(head)
+}
+
+/// # Returning value
+///
+/// Returns `Vec<String>` - names of all formats in the process-wide registry
+pub fn format_names() -> Vec<String> {
+    global().read().unwrap_or_else(|e| e.into_inner()).names()
+}
+
+/// Pseudo-format, which detects the actual format of the input when reading
+///
+/// Detection runs against formats in the process-wide registry.
+/// Empty input yields no transactions. The format can not be used for writing.
+pub struct AutoFormat;
+
+impl TransactionFormat for AutoFormat {
+    fn name(&self) -> &str {
+        AUTO_FORMAT_NAME
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        self.reader_with_options(reader, &ReadOptions::default())
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        let mut reader = BufReader::new(reader);
+        let detected = match reader.fill_buf() {
+            Ok([]) => return Box::new(std::iter::empty()),
+            Ok(head) => detect_format(head),
+            Err(e) => return Box::new(std::iter::once(Err(e.into()))),
+        };
+        match detected {
+            Some(format) => format.reader_with_options(Box::new(reader), options),
+            None => Box::new(std::iter::once(Err(ParseErrorKind::FormatNotDetected.into()))),
+        }
+    }
+
+    fn writer<'a>(&self, _writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(AutoFormatWriter)
+    }
+}
+
+/// Writer returned for `AutoFormat`, rejecting every write
+struct AutoFormatWriter;
+
+impl TransactionWriter for AutoFormatWriter {
+    fn write_one(&mut self, _transaction: &Transaction) -> Result<(), ParseError> {
+        Err(ParseErrorKind::FormatNotDetected.into())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        Err(ParseErrorKind::FormatNotDetected.into())
+    }
+}
@@ -1,14 +1,59 @@
-use crate::errors::ParseError;
+use crate::errors::{Location, ParseError, ParseErrorKind};
+use crate::format::common::{currency_minor_units, split_at_records};
+use crate::format::{Chunk, ReadOptions, TransactionFormat, TransactionIter, TransactionWriter, WriteOptions};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::money::Money;
 use crate::model::transaction::Transaction;
+use crate::model::transaction_ref::TransactionRef;
 use crate::model::transaction_status::TransactionStatus;
 use crate::model::transaction_type::TransactionType;
+use crate::validation::check_amount;
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use std::io::ErrorKind;
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 
 /// Magic value used for record separation
 pub const MAGIC: [u8; 4] = [0x59, 0x50, 0x42, 0x4E];
+/// Magic value of the file header
+pub const FILE_MAGIC: [u8; 4] = [0x59, 0x50, 0x42, 0x46];
+/// Headerless layout, records start at the beginning of the input
+pub const VERSION_1: u16 = 1;
+/// Layout with the file header, containing version and flags
+pub const VERSION_2: u16 = 2;
+/// The newest supported layout version
+pub const LATEST_VERSION: u16 = VERSION_2;
+/// File header flag: every record is followed by CRC32 of the record bytes
+pub const FLAG_CHECKSUMS: u16 = 0x0001;
+/// File header flag: records are followed by the index footer
+pub const FLAG_INDEX: u16 = 0x0002;
+/// Magic value of the index footer
+pub const INDEX_MAGIC: [u8; 4] = [0x59, 0x50, 0x42, 0x49];
+/// Flags, which can be set in the file header
+const KNOWN_FLAGS: u16 = FLAG_CHECKSUMS | FLAG_INDEX;
+/// Size of the index footer offset, closing the file
+const INDEX_OFFSET_SIZE: u64 = 8;
+/// Size of the record checksum trailer
+pub(crate) const CHECKSUM_SIZE: usize = 4;
+/// Size of the file magic, version and flags fields
+pub(crate) const FILE_HEADER_SIZE: usize = 8;
 const FIXED_RECORD_SIZE: usize = 42;
+/// Size of the description length field, which is not counted in the record size
+pub(crate) const DESC_LEN_FIELD_SIZE: usize = 4;
+/// Size of the magic and record size fields, preceding the record body
+pub(crate) const RECORD_HEADER_SIZE: usize = 8;
+/// Position of the transaction type in the record body
+const TX_TYPE_POSITION: usize = 8;
+/// Position of the transaction status in the record body
+const STATUS_POSITION: usize = 41;
+/// Version of the money extension, following the description
+///
+/// The extension consists of the version byte, ASCII currency code and scale byte.
+/// Records without extension hold amounts in the default currency. Newer versions
+/// may only append fields, so their extra bytes are ignored.
+const MONEY_EXTENSION_VERSION: u8 = 1;
+/// Size of the money extension of `MONEY_EXTENSION_VERSION`
+const MONEY_EXTENSION_SIZE: usize = 5;
 
 /// Reading and writing data in YPBankBin format
 pub struct YPBankBinParser;
@@ -28,19 +73,28 @@ impl YPBankBinParser {
     /// - `Ok(Vec<Transaction>)` — successful parsing, contains transaction vector
     /// - `Err(ParseError)` — parsing error (I/O, CSV, validation etc.)
     pub fn parse<R: Read>(reader: &mut R) -> Result<Vec<Transaction>, ParseError> {
-        std::iter::from_fn(|| {
-            match Self::parse_record(reader) {
-                Ok(Some(transaction)) => Some(Ok(transaction)),
-                // all bytes been read - stop iteration
-                Ok(None) => None,
-                Err(e) => Some(Err(e)),
-            }
-        }).collect()
+        Self::iter(reader).collect()
+    }
+
+    /// Creates a streaming reader over data in YPBankBin format.
+    ///
+    /// Records are parsed lazily one at a time.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which binary data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankBinReader<R>` - iterator over parsed transactions
+    pub fn iter<R: Read>(reader: R) -> YPBankBinReader<R> {
+        YPBankBinReader::new(reader)
     }
 
     /// Writes transaction vector into chosen sink in YPBankBin format.
     ///
     /// Function serializes every transaction into a byte stream and writes it into `writer`.
+    /// `VERSION_2` with the money extension is written, if any amount needs it.
     ///
     /// # Parameters
     ///
@@ -53,84 +107,208 @@ impl YPBankBinParser {
     /// - `Ok(())` — all transactions successfully written
     /// - `Err(ParseError)` — write or validation error
     pub fn write<W: Write>(transactions: &[Transaction], writer: &mut W) -> Result<(), ParseError> {
+        let options = WriteOptions::for_transactions(transactions);
+        let mut bin_writer = Self::writer(writer).with_options(&options);
         for transaction in transactions {
-            writer.write_all(&MAGIC)?;
-            let desc_len = transaction.description.len() as u32;
-            let record_size = FIXED_RECORD_SIZE as u32 + desc_len;
-            writer.write_u32::<BigEndian>(record_size)?;
-            writer.write_u64::<BigEndian>(transaction.tx_id)?;
-            let tx_type_byte = transaction.tx_type.to_u8();
-            writer.write_all(&[tx_type_byte])?;
-            writer.write_u64::<BigEndian>(transaction.from_user_id)?;
-            writer.write_u64::<BigEndian>(transaction.to_user_id)?;
-            let mut amount = transaction.amount;
-            if amount > 0 && transaction.tx_type == TransactionType::Withdrawal {
-                amount = -amount;
-            }
-            writer.write_i64::<BigEndian>(amount)?;
-            writer.write_u64::<BigEndian>(transaction.timestamp)?;
-            writer.write_all(&[transaction.status.to_u8()])?;
-            writer.write_u32::<BigEndian>(desc_len)?;
-            if desc_len > 0 {
-                writer.write_all(transaction.description.as_bytes())?;
-            }
+            bin_writer.write_one(transaction)?;
         }
-        writer.flush()?;
-        Ok(())
+        bin_writer.finish()
+    }
+
+    /// Creates an incremental writer of transactions in YPBankBin format.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which binary data will be written
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankBinWriter<W>` - writer, serializing transactions one at a time
+    pub fn writer<W: Write>(writer: W) -> YPBankBinWriter<W> {
+        YPBankBinWriter::new(writer)
+    }
+
+    /// Reads the file header, if the input starts with it
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(FileHeader, Option<[u8; 4]>), ParseError>`:
+    /// - `Ok((FileHeader, Option<[u8; 4]>))` — file header and magic of the first record,
+    ///   if it has been read instead of the header
+    /// - `Err(ParseError)` — I/O error or unsupported header
+    pub(crate) fn read_header<R: Read>(reader: &mut R) -> Result<(FileHeader, Option<[u8; 4]>), ParseError> {
+        let mut magic_buf = [0u8; 4];
+        match reader.read_exact(&mut magic_buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok((FileHeader::default(), None)),
+            Err(e) => return Err(Self::make_error(ParseErrorKind::Io(e), "MAGIC", 0)),
+        }
+        if magic_buf != FILE_MAGIC {
+            return Ok((FileHeader::default(), Some(magic_buf)));
+        }
+        let version = reader.read_u16::<BigEndian>()
+            .map_err(|e| Self::make_error(ParseErrorKind::Io(e), "VERSION", 4))?;
+        if !(VERSION_2..=LATEST_VERSION).contains(&version) {
+            return Err(Self::make_error(ParseErrorKind::UnsupportedVersion(version), "VERSION", 4));
+        }
+        let flags = reader.read_u16::<BigEndian>()
+            .map_err(|e| Self::make_error(ParseErrorKind::Io(e), "FLAGS", 6))?;
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(Self::make_error(ParseErrorKind::UnsupportedFlags(flags), "FLAGS", 6));
+        }
+        Ok((FileHeader { version, flags }, None))
     }
 
     /// Parses a single record to return a transaction
-    fn parse_record<R: Read>(reader: &mut R) -> Result<Option<Transaction>, ParseError> {
+    ///
+    /// `offset` points to the record beginning and is moved past the record.
+    /// `flags` are file header flags, describing the record layout.
+    /// `default_currency` is the currency of records without the money extension.
+    fn parse_record<R: Read>(
+        reader: &mut R,
+        offset: &mut u64,
+        flags: u16,
+        default_currency: CurrencyCode,
+    ) -> Result<Option<Transaction>, ParseError> {
+        let record_offset = *offset;
         let mut magic_buf = [0u8; 4];
         match reader.read_exact(&mut magic_buf) {
+            // Index footer follows the last record
+            Ok(()) if flags & FLAG_INDEX != 0 && magic_buf == INDEX_MAGIC => return Ok(None),
             Ok(()) => {
                 if magic_buf != MAGIC {
-                    return Err(ParseError::InvalidMagic(magic_buf, MAGIC));
+                    return Err(Self::make_error(
+                        ParseErrorKind::InvalidMagic(magic_buf, MAGIC), "MAGIC", record_offset
+                    ));
                 }
             }
             // Absence of the next magic value means all records are read
             Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
                 return Ok(None);
             }
-            Err(e) => return Err(ParseError::Io(e)),
+            Err(e) => return Err(Self::make_error(ParseErrorKind::Io(e), "MAGIC", record_offset)),
+        }
+        let record_size = reader.read_u32::<BigEndian>()
+            .map_err(|e| Self::make_error(ParseErrorKind::Io(e), "RECORD_SIZE", record_offset + 4))?;
+        let body_size = record_size as u64 + DESC_LEN_FIELD_SIZE as u64;
+        let mut body = Vec::new();
+        reader.by_ref().take(body_size).read_to_end(&mut body)?;
+        if (body.len() as u64) < body_size {
+            return Err(Self::make_error(
+                ParseErrorKind::Io(ErrorKind::UnexpectedEof.into()),
+                "RECORD_SIZE",
+                record_offset + 4,
+            ));
+        }
+        *offset = record_offset + RECORD_HEADER_SIZE as u64 + body_size;
+        if flags & FLAG_CHECKSUMS != 0 {
+            let checksum_offset = *offset;
+            let expected = reader.read_u32::<BigEndian>()
+                .map_err(|e| Self::make_error(ParseErrorKind::Io(e), "CHECKSUM", checksum_offset))?;
+            *offset += CHECKSUM_SIZE as u64;
+            let actual = Self::checksum(&magic_buf, record_size, &body);
+            if actual != expected {
+                return Err(Self::make_error(
+                    ParseErrorKind::ChecksumMismatch(expected, actual), "CHECKSUM", checksum_offset
+                ));
+            }
+        }
+        Self::parse_body(&body, record_size, record_offset, default_currency).map(|transaction| Some(transaction.to_transaction()))
+    }
+
+    /// Parses record body, following the record size, to return a transaction
+    ///
+    /// The whole body is already read, so an invalid body does not break
+    /// reading of the next records.
+    pub(crate) fn parse_body(
+        body: &[u8],
+        record_size: u32,
+        record_offset: u64,
+        default_currency: CurrencyCode,
+    ) -> Result<TransactionRef<'_>, ParseError> {
+        if (record_size as usize) < FIXED_RECORD_SIZE {
+            return Err(Self::make_error(
+                ParseErrorKind::InvalidData(format!(
+                    "Invalid data size: expected at least {}, actual {}",
+                    FIXED_RECORD_SIZE, record_size
+                )),
+                "RECORD_SIZE",
+                record_offset + 4,
+            ));
         }
-        let record_size = reader.read_u32::<BigEndian>()?;
+        let mut reader = body;
+        // offset of the next unread body byte from the beginning of the input
+        let position = |reader: &[u8]| record_offset + (RECORD_HEADER_SIZE + body.len() - reader.len()) as u64;
         let tx_id = reader.read_u64::<BigEndian>()?;
+        let tx_type_offset = position(reader);
         let mut tx_type_buf = [0u8; 1];
         reader.read_exact(&mut tx_type_buf)?;
-        let tx_type = TransactionType::from_u8(tx_type_buf[0])?;
+        let tx_type = TransactionType::from_u8(tx_type_buf[0])
+            .map_err(|e| e.with_location(Self::field_location("TX_TYPE", tx_type_offset)))?;
+        let from_user_id_offset = position(reader);
         let from_user_id = reader.read_u64::<BigEndian>()?;
+        let to_user_id_offset = position(reader);
         let to_user_id = reader.read_u64::<BigEndian>()?;
+        let amount_offset = position(reader);
         let mut amount = reader.read_i64::<BigEndian>()?;
         if (amount > 0 && tx_type == TransactionType::Withdrawal) 
             || (amount < 0 && tx_type != TransactionType::Withdrawal) {
-            return Err(ParseError::InvalidAmountForTransactionType(amount, tx_type));
+            return Err(Self::make_error(
+                ParseErrorKind::InvalidAmountForTransactionType(amount, tx_type), "AMOUNT", amount_offset
+            ));
         }
         if amount < 0 {
             amount = -amount;
         }
         let timestamp = reader.read_u64::<BigEndian>()?;
+        let status_offset = position(reader);
         let mut status_buf = [0u8; 1];
         reader.read_exact(&mut status_buf)?;
-        let status = TransactionStatus::from_u8(status_buf[0])?;
+        let status = TransactionStatus::from_u8(status_buf[0])
+            .map_err(|e| e.with_location(Self::field_location("STATUS", status_offset)))?;
         let desc_len = reader.read_u32::<BigEndian>()?;
         let expected_size = FIXED_RECORD_SIZE as u32 + desc_len;
-        if record_size != expected_size {
-            return Err(ParseError::InvalidData(format!(
-                "Invalid data size: expected {}, actual {}",
-                expected_size, record_size
-            )));
-        }
-        let description = if desc_len > 0 {
-            let mut desc_buf = vec![0u8; desc_len as usize];
-            reader.read_exact(&mut desc_buf)?;
-            String::from_utf8(desc_buf)
-                .map_err(|e| ParseError::InvalidDescription(e.to_string()))?
+        let extension_size = record_size
+            .checked_sub(expected_size)
+            .filter(|&size| size == 0 || size as usize >= MONEY_EXTENSION_SIZE)
+            .ok_or_else(|| Self::make_error(
+                ParseErrorKind::InvalidRecordSize(expected_size, record_size), "RECORD_SIZE", record_offset + 4
+            ))?;
+        let description_offset = position(reader);
+        let desc_buf = reader
+            .get(..desc_len as usize)
+            .ok_or_else(|| ParseError::from(ParseErrorKind::Io(ErrorKind::UnexpectedEof.into())))?;
+        reader = &reader[desc_buf.len()..];
+        let description = std::str::from_utf8(desc_buf).map_err(|e| Self::make_error(
+            ParseErrorKind::InvalidDescription(e.to_string()), "DESCRIPTION", description_offset
+        ))?;
+        let amount = if extension_size == 0 {
+            Money::new(amount, default_currency)
         } else {
-            String::new()
+            let extension_offset = position(reader);
+            let version = reader.read_u8()?;
+            if version < MONEY_EXTENSION_VERSION {
+                return Err(Self::make_error(
+                    ParseErrorKind::InvalidData(format!("Unsupported record extension version {}", version)),
+                    "EXTENSION",
+                    extension_offset,
+                ));
+            }
+            let currency_offset = position(reader);
+            let mut currency_buf = [0u8; 3];
+            reader.read_exact(&mut currency_buf)?;
+            let currency = CurrencyCode::from_bytes(currency_buf).ok_or_else(|| Self::make_error(
+                ParseErrorKind::InvalidCurrency(String::from_utf8_lossy(&currency_buf).into_owned()),
+                "CURRENCY",
+                currency_offset,
+            ))?;
+            let scale_offset = position(reader);
+            let scale = reader.read_u8()?;
+            Money::with_scale(amount, currency, scale)
+                .map_err(|e| e.with_location(Self::field_location("SCALE", scale_offset)))?
         };
-        
-        let transaction = Transaction {
+
+        let transaction = TransactionRef {
             tx_id,
             tx_type,
             from_user_id,
@@ -140,7 +318,749 @@ impl YPBankBinParser {
             status,
             description
         };
-        transaction.validate()?;
-        Ok(Some(transaction))
+        transaction.validate().map_err(|e| {
+            let offset = match e.location().field.as_deref() {
+                Some("FROM_USER_ID") => from_user_id_offset,
+                Some("TO_USER_ID") => to_user_id_offset,
+                Some("AMOUNT") => amount_offset,
+                _ => record_offset,
+            };
+            e.with_location(Location {
+                byte_offset: Some(offset),
+                ..Location::default()
+            })
+        })?;
+        Ok(transaction)
+    }
+
+    /// Reads the index footer
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Vec<(u64, u64)>, ParseError>`:
+    /// - `Ok(Vec<(u64, u64)>)` — pairs of transaction ID and record offset
+    /// - `Err(ParseError)` — I/O error or invalid footer
+    fn read_index<R: Read + Seek>(reader: &mut R) -> Result<Vec<(u64, u64)>, ParseError> {
+        let end = reader.seek(SeekFrom::End(0))?;
+        let offset_position = end.checked_sub(INDEX_OFFSET_SIZE).ok_or_else(|| {
+            Self::make_error(ParseErrorKind::Io(ErrorKind::UnexpectedEof.into()), "INDEX", end)
+        })?;
+        reader.seek(SeekFrom::Start(offset_position))?;
+        let index_offset = reader.read_u64::<BigEndian>()?;
+        reader.seek(SeekFrom::Start(index_offset))?;
+        let mut magic_buf = [0u8; 4];
+        reader.read_exact(&mut magic_buf)
+            .map_err(|e| Self::make_error(ParseErrorKind::Io(e), "INDEX", index_offset))?;
+        if magic_buf != INDEX_MAGIC {
+            return Err(Self::make_error(
+                ParseErrorKind::InvalidMagic(magic_buf, INDEX_MAGIC), "INDEX", index_offset
+            ));
+        }
+        let count = reader.read_u64::<BigEndian>()?;
+        if count > (offset_position - index_offset) / 16 {
+            return Err(Self::make_error(
+                ParseErrorKind::InvalidData(format!("Invalid index size {}", count)), "INDEX", index_offset + 4
+            ));
+        }
+        let mut index = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let tx_id = reader.read_u64::<BigEndian>()?;
+            let record_offset = reader.read_u64::<BigEndian>()?;
+            index.push((tx_id, record_offset));
+        }
+        Ok(index)
+    }
+
+    /// Checks if the input at a found magic value looks like a valid record
+    ///
+    /// The record size must fit the fixed fields, the description and the money extension,
+    /// the whole record and its checksum must be present, type and status bytes must be known.
+    /// `reader` points past the magic value.
+    fn is_record_candidate<R: Read>(reader: &mut R, checksums: bool) -> bool {
+        let Ok(record_size) = reader.read_u32::<BigEndian>() else {
+            return false;
+        };
+        if (record_size as usize) < FIXED_RECORD_SIZE {
+            return false;
+        }
+        let body_size = record_size as u64 + DESC_LEN_FIELD_SIZE as u64;
+        let mut body = Vec::new();
+        if reader.by_ref().take(body_size).read_to_end(&mut body).is_err() || (body.len() as u64) < body_size {
+            return false;
+        }
+        let desc_len_bytes = [body[FIXED_RECORD_SIZE], body[FIXED_RECORD_SIZE + 1],
+            body[FIXED_RECORD_SIZE + 2], body[FIXED_RECORD_SIZE + 3]];
+        let expected_size = FIXED_RECORD_SIZE as u64 + u32::from_be_bytes(desc_len_bytes) as u64;
+        let extension_size = match (record_size as u64).checked_sub(expected_size) {
+            Some(size) => size,
+            None => return false,
+        };
+        if extension_size != 0 && extension_size < MONEY_EXTENSION_SIZE as u64 {
+            return false;
+        }
+        if TransactionType::from_u8(body[TX_TYPE_POSITION]).is_err()
+            || TransactionStatus::from_u8(body[STATUS_POSITION]).is_err() {
+            return false;
+        }
+        !checksums || reader
+            .read_u32::<BigEndian>()
+            .is_ok_and(|checksum| checksum == Self::checksum(&MAGIC, record_size, &body))
+    }
+
+    /// Calculates CRC32 of the record bytes
+    pub(crate) fn checksum(magic: &[u8; 4], record_size: u32, body: &[u8]) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(magic);
+        hasher.update(&record_size.to_be_bytes());
+        hasher.update(body);
+        hasher.finalize()
+    }
+
+    fn field_location(field_name: &str, byte_offset: u64) -> Location {
+        Location {
+            byte_offset: Some(byte_offset),
+            field: Some(field_name.to_string()),
+            ..Location::default()
+        }
+    }
+
+    pub(crate) fn make_error(kind: ParseErrorKind, field_name: &str, byte_offset: u64) -> ParseError {
+        ParseError::new(kind).with_location(Self::field_location(field_name, byte_offset))
+    }
+}
+
+impl TransactionFormat for YPBankBinParser {
+    fn name(&self) -> &str {
+        "binary"
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        Box::new(YPBankBinParser::iter(reader))
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        Box::new(YPBankBinParser::iter(reader).with_default_currency(options.default_currency))
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(YPBankBinParser::writer(writer))
+    }
+
+    fn writer_with_options<'a>(
+        &self,
+        writer: Box<dyn Write + 'a>,
+        options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(YPBankBinParser::writer(writer).with_options(options))
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        head.starts_with(&MAGIC) || head.starts_with(&FILE_MAGIC)
+    }
+
+    fn split<'d>(&self, data: &'d [u8], max_chunks: usize) -> Option<Vec<Chunk<'d>>> {
+        let (header, _) = YPBankBinParser::read_header(&mut &data[..]).ok()?;
+        let (prefix, mut position) = if header.version > VERSION_1 {
+            (&data[..FILE_HEADER_SIZE], FILE_HEADER_SIZE)
+        } else {
+            (&data[..0], 0)
+        };
+        let trailer_size = if header.flags & FLAG_CHECKSUMS != 0 { CHECKSUM_SIZE } else { 0 };
+        let mut starts = vec![(position, 0)];
+        // walk record sizes, anything irregular is left to the reader of the last chunk
+        while data[position..].starts_with(&MAGIC) {
+            let Some(size_bytes) = data.get(position + MAGIC.len()..position + RECORD_HEADER_SIZE) else {
+                break;
+            };
+            let record_size = u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]]);
+            position += RECORD_HEADER_SIZE + record_size as usize + DESC_LEN_FIELD_SIZE + trailer_size;
+            if position >= data.len() {
+                break;
+            }
+            starts.push((position, 0));
+        }
+        Some(split_at_records(data, prefix, &starts, max_chunks))
+    }
+}
+
+/// Header of YPBankBin file
+///
+/// Files of `VERSION_1` have no header, so for them
+/// the default header with no flags is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileHeader {
+    /// Layout version
+    pub version: u16,
+    /// Optional features of the layout
+    pub flags: u16,
+}
+
+impl Default for FileHeader {
+    fn default() -> Self {
+        Self {
+            version: VERSION_1,
+            flags: 0,
+        }
+    }
+}
+
+/// Incremental writer of transactions in YPBankBin format
+///
+/// Writes `VERSION_1` layout unless another version is chosen with `with_version`.
+/// File header is written exactly once: before the first record or on `finish`.
+/// Money extension with currency and scale is written only in `VERSION_2` and later,
+/// `VERSION_2` replaces the default `VERSION_1` automatically, if the first record needs
+/// the extension; a version, chosen with `with_version`, is kept.
+pub struct YPBankBinWriter<W: Write> {
+    writer: W,
+    header: FileHeader,
+    version_chosen: bool,
+    header_written: bool,
+    offset: u64,
+    index: Option<Vec<(u64, u64)>>,
+}
+
+impl<W: Write> YPBankBinWriter<W> {
+    /// Creates a new writer
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which binary data will be written
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            header: FileHeader::default(),
+            version_chosen: false,
+            header_written: false,
+            offset: 0,
+            index: None,
+        }
+    }
+
+    /// Chooses the layout version to write
+    ///
+    /// The chosen version is not replaced for records, which need the money extension,
+    /// such records are rejected in `VERSION_1`.
+    ///
+    /// # Parameters
+    ///
+    /// * `version` — layout version from `VERSION_1` to `LATEST_VERSION`
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Self, ParseError>`:
+    /// - `Ok(Self)` — writer of the chosen version
+    /// - `Err(ParseError)` — version is not supported
+    pub fn with_version(mut self, version: u16) -> Result<Self, ParseError> {
+        if !(VERSION_1..=LATEST_VERSION).contains(&version) {
+            return Err(ParseErrorKind::UnsupportedVersion(version).into());
+        }
+        if version == VERSION_1 && self.header.flags != 0 {
+            return Err(ParseErrorKind::UnsupportedFlags(self.header.flags).into());
+        }
+        self.header.version = version;
+        self.version_chosen = true;
+        Ok(self)
+    }
+
+    /// Enables CRC32 trailer after every record
+    ///
+    /// Checksums are announced in the file header, so the headerless
+    /// `VERSION_1` is replaced with `VERSION_2`.
+    pub fn with_checksums(mut self) -> Self {
+        self.header.flags |= FLAG_CHECKSUMS;
+        self.header.version = self.header.version.max(VERSION_2);
+        self
+    }
+
+    /// Enables the index footer, mapping transaction IDs to record offsets
+    ///
+    /// The footer is written on `finish` and allows `YPBankBinReader::get`
+    /// to seek straight to a record. As with checksums, `VERSION_1` is replaced with `VERSION_2`.
+    pub fn with_index(mut self) -> Self {
+        self.header.flags |= FLAG_INDEX;
+        self.header.version = self.header.version.max(VERSION_2);
+        self.index = Some(Vec::new());
+        self
+    }
+
+    /// Applies the writing settings
+    ///
+    /// Currencies are written in the money extension, so with `currencies`
+    /// the default `VERSION_1` is replaced with `VERSION_2`.
+    ///
+    /// # Parameters
+    ///
+    /// * `options` — writing settings
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankBinWriter<W>` - the writer with the settings
+    pub fn with_options(mut self, options: &WriteOptions) -> Self {
+        if options.currencies && !self.version_chosen {
+            self.header.version = self.header.version.max(VERSION_2);
+        }
+        self
+    }
+
+    /// Writes file header if the version has one and it has not been written yet
+    fn ensure_header(&mut self) -> Result<(), ParseError> {
+        if !self.header_written && self.header.version > VERSION_1 {
+            self.writer.write_all(&FILE_MAGIC)?;
+            self.writer.write_u16::<BigEndian>(self.header.version)?;
+            self.writer.write_u16::<BigEndian>(self.header.flags)?;
+            self.offset = FILE_HEADER_SIZE as u64;
+        }
+        self.header_written = true;
+        Ok(())
+    }
+}
+
+impl<W: Write> TransactionWriter for YPBankBinWriter<W> {
+    fn write_one(&mut self, transaction: &Transaction) -> Result<(), ParseError> {
+        check_amount(transaction)?;
+        let needs_extension = transaction.amount.currency != DEFAULT_CURRENCY
+            || transaction.amount.scale != transaction.amount.currency.minor_unit();
+        if needs_extension && !self.header_written && !self.version_chosen && self.header.version == VERSION_1 {
+            self.header.version = VERSION_2;
+        }
+        let has_extension = self.header.version > VERSION_1;
+        let mut amount = if has_extension {
+            transaction.amount.minor_units
+        } else if transaction.amount.currency != DEFAULT_CURRENCY {
+            return Err(ParseError::new(ParseErrorKind::Validation(format!(
+                "amount in {} can not be written in version {} layout, \
+                version {} is chosen for the first record or by WriteOptions::currencies",
+                transaction.amount.currency, VERSION_1, VERSION_2
+            )))
+            .with_field("AMOUNT"));
+        } else {
+            currency_minor_units(&transaction.amount)?
+        };
+        self.ensure_header()?;
+        let desc_len = transaction.description.len() as u32;
+        let extension_size = if has_extension { MONEY_EXTENSION_SIZE as u32 } else { 0 };
+        let record_size = FIXED_RECORD_SIZE as u32 + desc_len + extension_size;
+        let mut record = Vec::with_capacity(
+            RECORD_HEADER_SIZE + DESC_LEN_FIELD_SIZE + record_size as usize + CHECKSUM_SIZE
+        );
+        let writer = &mut record;
+        writer.write_all(&MAGIC)?;
+        writer.write_u32::<BigEndian>(record_size)?;
+        writer.write_u64::<BigEndian>(transaction.tx_id)?;
+        let tx_type_byte = transaction.tx_type.to_u8();
+        writer.write_all(&[tx_type_byte])?;
+        writer.write_u64::<BigEndian>(transaction.from_user_id)?;
+        writer.write_u64::<BigEndian>(transaction.to_user_id)?;
+        if amount > 0 && transaction.tx_type == TransactionType::Withdrawal {
+            amount = -amount;
+        }
+        writer.write_i64::<BigEndian>(amount)?;
+        writer.write_u64::<BigEndian>(transaction.timestamp)?;
+        writer.write_all(&[transaction.status.to_u8()])?;
+        writer.write_u32::<BigEndian>(desc_len)?;
+        if desc_len > 0 {
+            writer.write_all(transaction.description.as_bytes())?;
+        }
+        if has_extension {
+            writer.write_all(&[MONEY_EXTENSION_VERSION])?;
+            writer.write_all(&transaction.amount.currency.to_bytes())?;
+            writer.write_all(&[transaction.amount.scale])?;
+        }
+        if self.header.flags & FLAG_CHECKSUMS != 0 {
+            let checksum = crc32fast::hash(&record);
+            record.write_u32::<BigEndian>(checksum)?;
+        }
+        self.writer.write_all(&record)?;
+        if let Some(index) = &mut self.index {
+            index.push((transaction.tx_id, self.offset));
+        }
+        self.offset += record.len() as u64;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.ensure_header()?;
+        if let Some(mut index) = self.index.take() {
+            // stable sort keeps the first record of a repeated ID first
+            index.sort_by_key(|&(tx_id, _)| tx_id);
+            self.writer.write_all(&INDEX_MAGIC)?;
+            self.writer.write_u64::<BigEndian>(index.len() as u64)?;
+            for (tx_id, record_offset) in index {
+                self.writer.write_u64::<BigEndian>(tx_id)?;
+                self.writer.write_u64::<BigEndian>(record_offset)?;
+            }
+            self.writer.write_u64::<BigEndian>(self.offset)?;
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Reader, which can return already consumed bytes back to the input
+///
+/// Reads the inner reader in blocks. When recording is started with `mark`,
+/// consumed bytes are kept, so the input can be rewound to them.
+struct RewindReader<R: Read> {
+    inner: R,
+    buffer: Vec<u8>,
+    position: usize,
+    history: Option<Vec<u8>>,
+}
+
+impl<R: Read> RewindReader<R> {
+    const BLOCK_SIZE: usize = 8 * 1024;
+
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            position: 0,
+            history: None,
+        }
+    }
+
+    /// Drops buffered bytes and gives access to the inner reader
+    fn inner_mut(&mut self) -> &mut R {
+        self.buffer.clear();
+        self.position = 0;
+        self.history = None;
+        &mut self.inner
+    }
+
+    /// Returns bytes to the input, so they are read again
+    fn unread(&mut self, bytes: &[u8]) {
+        let mut buffer = Vec::with_capacity(bytes.len() + self.buffer.len() - self.position);
+        buffer.extend_from_slice(bytes);
+        buffer.extend_from_slice(&self.buffer[self.position..]);
+        self.buffer = buffer;
+        self.position = 0;
+    }
+
+    /// Starts recording of consumed bytes
+    fn mark(&mut self) {
+        self.history = Some(Vec::new());
+    }
+
+    /// Stops recording and returns recorded bytes after the first `skip` ones to the input
+    fn rewind(&mut self, skip: usize) {
+        if let Some(history) = self.history.take() {
+            self.unread(&history[skip.min(history.len())..]);
+        }
+    }
+}
+
+impl<R: Read> Read for RewindReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position == self.buffer.len() {
+            self.buffer.resize(Self::BLOCK_SIZE, 0);
+            let read = self.inner.read(&mut self.buffer).inspect_err(|_| self.buffer.clear())?;
+            self.buffer.truncate(read);
+            self.position = 0;
+        }
+        let read = buf.len().min(self.buffer.len() - self.position);
+        buf[..read].copy_from_slice(&self.buffer[self.position..self.position + read]);
+        self.position += read;
+        if let Some(history) = &mut self.history {
+            history.extend_from_slice(&buf[..read]);
+        }
+        Ok(read)
+    }
+}
+
+/// Streaming reader of transactions in YPBankBin format
+///
+/// Yields a transaction for every record. A record with invalid content is reported
+/// as an error and skipped using its record size, iteration stops after invalid
+/// magic or an I/O error. File header, if present, is read before the first record.
+///
+/// With `with_resync` the reader recovers from corrupted record boundaries instead:
+/// it scans forward for the next valid record and reports the skipped bytes
+/// as `ParseErrorKind::SkippedBytes`.
+pub struct YPBankBinReader<R: Read> {
+    reader: RewindReader<R>,
+    offset: u64,
+    record_index: usize,
+    header: Option<FileHeader>,
+    resync: bool,
+    skipped: Vec<Range<u64>>,
+    index: Option<Vec<(u64, u64)>>,
+    default_currency: CurrencyCode,
+    finished: bool,
+}
+
+impl<R: Read> YPBankBinReader<R> {
+    /// Creates a new reader
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which binary data can be read
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: RewindReader::new(reader),
+            offset: 0,
+            record_index: 0,
+            header: None,
+            resync: false,
+            skipped: Vec::new(),
+            index: None,
+            default_currency: DEFAULT_CURRENCY,
+            finished: false,
+        }
+    }
+
+    /// Enables recovery from invalid magic values, truncated records
+    /// and records, which do not match their record size or checksum
+    ///
+    /// Instead of stopping or trusting the record size, the reader searches for the next
+    /// magic value after the broken record start, followed by a record of consistent size,
+    /// and continues from it.
+    pub fn with_resync(mut self) -> Self {
+        self.resync = true;
+        self
+    }
+
+    /// Chooses currency of records without the money extension
+    ///
+    /// # Parameters
+    ///
+    /// * `currency` — currency of amounts, `DEFAULT_CURRENCY` unless chosen
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankBinReader<R>` - the reader with the default currency
+    pub fn with_default_currency(mut self, currency: CurrencyCode) -> Self {
+        self.default_currency = currency;
+        self
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `Option<FileHeader>` - header of the file, `None` before the first record is requested
+    pub fn header(&self) -> Option<FileHeader> {
+        self.header
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `&[Range<u64>]` - byte ranges, skipped while resynchronizing
+    pub fn skipped_ranges(&self) -> &[Range<u64>] {
+        &self.skipped
+    }
+
+    fn flags(&self) -> u16 {
+        self.header.map_or(0, |header| header.flags)
+    }
+
+    /// Reads the file header before the first record
+    fn ensure_header(&mut self) -> Result<(), ParseError> {
+        if self.header.is_none() {
+            let (header, magic) = YPBankBinParser::read_header(&mut self.reader)?;
+            if header.version > VERSION_1 {
+                self.offset = FILE_HEADER_SIZE as u64;
+            }
+            self.header = Some(header);
+            if let Some(magic) = magic {
+                self.reader.unread(&magic);
+            }
+        }
+        Ok(())
+    }
+
+    /// Tells if the error means that record boundaries are lost
+    ///
+    /// A record, which does not match its record size or checksum, may have a corrupted
+    /// record size, so the next record can not be found by it.
+    fn is_corruption(error: &ParseError) -> bool {
+        match error.kind() {
+            ParseErrorKind::InvalidMagic(..)
+            | ParseErrorKind::InvalidRecordSize(..)
+            | ParseErrorKind::ChecksumMismatch(..)
+            | ParseErrorKind::InvalidData(_) => true,
+            ParseErrorKind::Io(e) => e.kind() == ErrorKind::UnexpectedEof,
+            _ => false,
+        }
+    }
+
+    /// Scans the input for the next valid record
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<bool, ParseError>`:
+    /// - `Ok(true)` — the input is positioned at the next valid record
+    /// - `Ok(false)` — the end of input is reached
+    /// - `Err(ParseError)` — I/O error
+    fn resync(&mut self) -> Result<bool, ParseError> {
+        let checksums = self.flags() & FLAG_CHECKSUMS != 0;
+        let mut window = [0u8; 4];
+        let mut filled = 0;
+        loop {
+            let byte = match self.reader.read_u8() {
+                Ok(byte) => byte,
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                    self.offset += filled as u64;
+                    return Ok(false);
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if filled == window.len() {
+                window.rotate_left(1);
+                self.offset += 1;
+                filled -= 1;
+            }
+            window[filled] = byte;
+            filled += 1;
+            if window == MAGIC {
+                self.reader.mark();
+                let valid = YPBankBinParser::is_record_candidate(&mut self.reader, checksums);
+                self.reader.rewind(0);
+                if valid {
+                    self.reader.unread(&MAGIC);
+                    return Ok(true);
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for YPBankBinReader<R> {
+    type Item = Result<Transaction, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if let Err(e) = self.ensure_header() {
+            self.finished = true;
+            return Some(Err(e));
+        }
+        let record_index = self.record_index;
+        self.record_index += 1;
+        let record_offset = self.offset;
+        if self.resync {
+            self.reader.mark();
+        }
+        let flags = self.flags();
+        let result = YPBankBinParser::parse_record(&mut self.reader, &mut self.offset, flags, self.default_currency);
+        let location = Location {
+            record_index: Some(record_index),
+            byte_offset: Some(record_offset),
+            ..Location::default()
+        };
+        match result {
+            Ok(Some(transaction)) => {
+                self.reader.history = None;
+                Some(Ok(transaction))
+            }
+            // all bytes been read - stop iteration
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) if self.resync && Self::is_corruption(&e) => {
+                // continue searching from the byte after the broken record start
+                self.reader.rewind(1);
+                self.offset = record_offset + 1;
+                match self.resync() {
+                    Ok(found) => {
+                        // skipped bytes are not a record, the found record keeps the index
+                        self.record_index = record_index;
+                        self.finished = !found;
+                        self.skipped.push(record_offset..self.offset);
+                        let kind = ParseErrorKind::SkippedBytes(record_offset, self.offset);
+                        Some(Err(ParseError::new(kind).with_location(Location {
+                            byte_offset: Some(record_offset),
+                            ..Location::default()
+                        })))
+                    }
+                    Err(e) => {
+                        self.finished = true;
+                        Some(Err(e.with_location(location)))
+                    }
+                }
+            }
+            Err(e) => {
+                self.reader.history = None;
+                self.finished = !e.is_recoverable();
+                Some(Err(e.with_location(location)))
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> YPBankBinReader<R> {
+    /// Finds a transaction by its ID
+    ///
+    /// Uses the index footer if the file has one, otherwise the index is built
+    /// by reading all records once. The reader is positioned after the found record,
+    /// so iteration continues with the next one.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx_id` — ID of the transaction
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Option<Transaction>, ParseError>`:
+    /// - `Ok(Some(Transaction))` — the first transaction with this ID
+    /// - `Ok(None)` — there is no transaction with this ID
+    /// - `Err(ParseError)` — I/O error, invalid index or invalid record
+    pub fn get(&mut self, tx_id: u64) -> Result<Option<Transaction>, ParseError> {
+        self.ensure_header()?;
+        if self.index.is_none() {
+            self.index = Some(self.load_index()?);
+        }
+        let index = self.index.as_deref().unwrap_or_default();
+        let position = index.partition_point(|&(id, _)| id < tx_id);
+        let Some(&(_, record_offset)) = index.get(position).filter(|&&(id, _)| id == tx_id) else {
+            return Ok(None);
+        };
+        self.reader.inner_mut().seek(SeekFrom::Start(record_offset))?;
+        self.offset = record_offset;
+        self.finished = false;
+        let location = Location {
+            byte_offset: Some(record_offset),
+            ..Location::default()
+        };
+        let flags = self.flags();
+        match YPBankBinParser::parse_record(&mut self.reader, &mut self.offset, flags, self.default_currency) {
+            Ok(Some(transaction)) => Ok(Some(transaction)),
+            Ok(None) => Err(YPBankBinParser::make_error(
+                ParseErrorKind::InvalidData(format!("Index points past the records: {}", record_offset)),
+                "INDEX",
+                record_offset,
+            )),
+            Err(e) => Err(e.with_location(location)),
+        }
+    }
+
+    /// Reads the index footer or builds the index from records
+    fn load_index(&mut self) -> Result<Vec<(u64, u64)>, ParseError> {
+        let flags = self.flags();
+        if flags & FLAG_INDEX != 0 {
+            return YPBankBinParser::read_index(self.reader.inner_mut());
+        }
+        let data_offset = if self.header.is_some_and(|header| header.version > VERSION_1) {
+            FILE_HEADER_SIZE as u64
+        } else {
+            0
+        };
+        self.reader.inner_mut().seek(SeekFrom::Start(data_offset))?;
+        let mut offset = data_offset;
+        let mut index = Vec::new();
+        loop {
+            let record_offset = offset;
+            match YPBankBinParser::parse_record(&mut self.reader, &mut offset, flags, self.default_currency) {
+                Ok(Some(transaction)) => index.push((transaction.tx_id, record_offset)),
+                Ok(None) => break,
+                Err(e) if e.is_recoverable() => {}
+                Err(e) => return Err(e.with_location(Location {
+                    byte_offset: Some(record_offset),
+                    ..Location::default()
+                })),
+            }
+        }
+        index.sort_by_key(|&(tx_id, _)| tx_id);
+        Ok(index)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,192 @@
+use crate::errors::{Location, ParseError, ParseErrorKind};
+use crate::format::yp_bank_bin_format::{
+    YPBankBinParser, CHECKSUM_SIZE, DESC_LEN_FIELD_SIZE, FILE_HEADER_SIZE, FLAG_CHECKSUMS, FLAG_INDEX,
+    INDEX_MAGIC, MAGIC, RECORD_HEADER_SIZE, VERSION_1,
+};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::transaction_ref::TransactionRef;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Memory-mapped file in YPBankBin format
+///
+/// Records are parsed straight from the mapped bytes without copying.
+pub struct YPBankBinMmap {
+    mmap: Mmap,
+}
+
+impl YPBankBinMmap {
+    /// Maps a file in YPBankBin format into memory
+    ///
+    /// The file must not be modified by other processes while it is mapped.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` — path to the file
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<YPBankBinMmap, ParseError>`:
+    /// - `Ok(YPBankBinMmap)` — mapped file
+    /// - `Err(ParseError)` — the file can not be opened or mapped
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only, concurrent modification of the file
+        // is documented as unsupported
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self { mmap })
+    }
+
+    /// # Returning value
+    ///
+    /// Returns `&[u8]` - mapped bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Creates a zero-copy reader over the mapped bytes
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankBinSliceReader` - iterator over borrowed transactions
+    pub fn iter(&self) -> YPBankBinSliceReader<'_> {
+        YPBankBinSliceReader::new(&self.mmap)
+    }
+}
+
+/// Zero-copy reader of transactions in YPBankBin format
+///
+/// Yields `TransactionRef` views with descriptions borrowed from the data.
+/// Handles invalid records the same way as `YPBankBinReader`.
+pub struct YPBankBinSliceReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    record_index: usize,
+    flags: Option<u16>,
+    default_currency: CurrencyCode,
+    finished: bool,
+}
+
+impl<'a> YPBankBinSliceReader<'a> {
+    /// Creates a new reader
+    ///
+    /// # Parameters
+    ///
+    /// * `data` — bytes in YPBankBin format
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            offset: 0,
+            record_index: 0,
+            flags: None,
+            default_currency: DEFAULT_CURRENCY,
+            finished: false,
+        }
+    }
+
+    /// Chooses currency of records without the money extension
+    ///
+    /// # Parameters
+    ///
+    /// * `currency` — currency of amounts, `DEFAULT_CURRENCY` unless chosen
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankBinSliceReader` - the reader with the default currency
+    pub fn with_default_currency(mut self, currency: CurrencyCode) -> Self {
+        self.default_currency = currency;
+        self
+    }
+
+    /// Reads the file header before the first record
+    fn read_header(&mut self) -> Result<u16, ParseError> {
+        if let Some(flags) = self.flags {
+            return Ok(flags);
+        }
+        let (header, _) = YPBankBinParser::read_header(&mut &self.data[..])?;
+        if header.version > VERSION_1 {
+            self.offset = FILE_HEADER_SIZE;
+        }
+        self.flags = Some(header.flags);
+        Ok(header.flags)
+    }
+
+    /// Parses the record at the current offset and moves past it
+    fn parse_record(&mut self, flags: u16) -> Result<Option<TransactionRef<'a>>, ParseError> {
+        let record_offset = self.offset;
+        let data = &self.data[record_offset..];
+        let eof = |field: &str, offset: usize| {
+            YPBankBinParser::make_error(ParseErrorKind::Io(ErrorKind::UnexpectedEof.into()), field, offset as u64)
+        };
+        // Absence of the next magic value means all records are read
+        let Some(magic) = data.get(..MAGIC.len()) else {
+            return Ok(None);
+        };
+        if flags & FLAG_INDEX != 0 && magic == INDEX_MAGIC {
+            return Ok(None);
+        }
+        if magic != MAGIC {
+            let mut actual = [0u8; 4];
+            actual.copy_from_slice(magic);
+            return Err(YPBankBinParser::make_error(
+                ParseErrorKind::InvalidMagic(actual, MAGIC), "MAGIC", record_offset as u64
+            ));
+        }
+        let size_bytes = data.get(MAGIC.len()..RECORD_HEADER_SIZE).ok_or_else(|| eof("RECORD_SIZE", record_offset + 4))?;
+        let record_size = u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]]);
+        let body_end = RECORD_HEADER_SIZE + record_size as usize + DESC_LEN_FIELD_SIZE;
+        let body = data.get(RECORD_HEADER_SIZE..body_end).ok_or_else(|| eof("RECORD_SIZE", record_offset + 4))?;
+        self.offset = record_offset + body_end;
+        if flags & FLAG_CHECKSUMS != 0 {
+            let trailer = data.get(body_end..body_end + CHECKSUM_SIZE).ok_or_else(|| eof("CHECKSUM", self.offset))?;
+            let expected = u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+            let actual = YPBankBinParser::checksum(&MAGIC, record_size, body);
+            let checksum_offset = self.offset;
+            self.offset += CHECKSUM_SIZE;
+            if actual != expected {
+                return Err(YPBankBinParser::make_error(
+                    ParseErrorKind::ChecksumMismatch(expected, actual), "CHECKSUM", checksum_offset as u64
+                ));
+            }
+        }
+        YPBankBinParser::parse_body(body, record_size, record_offset as u64, self.default_currency).map(Some)
+    }
+}
+
+impl<'a> Iterator for YPBankBinSliceReader<'a> {
+    type Item = Result<TransactionRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let flags = match self.read_header() {
+            Ok(flags) => flags,
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(e));
+            }
+        };
+        let record_index = self.record_index;
+        self.record_index += 1;
+        let record_offset = self.offset;
+        match self.parse_record(flags) {
+            Ok(Some(transaction)) => Some(Ok(transaction)),
+            // all bytes been read - stop iteration
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = !e.is_recoverable();
+                Some(Err(e.with_location(Location {
+                    record_index: Some(record_index),
+                    byte_offset: Some(record_offset as u64),
+                    ..Location::default()
+                })))
+            }
+        }
+    }
+}
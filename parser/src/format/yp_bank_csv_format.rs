@@ -1,17 +1,160 @@
 use crate::format::yp_bank_csv_format::Idx::{
     TxId, TxType, FromUserId, ToUserId,
-    Amount, Timestamp, Status, Description
+    Amount, Timestamp, Status, Description, Currency
 };
-use crate::errors::ParseError;
-use crate::format::common::{parse_number, parse_transaction_status, parse_transaction_type};
+use crate::errors::{Location, ParseError, ParseErrorKind};
+use crate::format::{ReadOptions, TransactionFormat, TransactionIter, TransactionWriter, WriteOptions};
+use crate::format::common::{
+    currency_minor_units, parse_currency, parse_number, parse_transaction_status, parse_transaction_type,
+};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::money::Money;
 use crate::model::transaction::Transaction;
+use crate::validation::check_amount;
 use csv::{ReaderBuilder, StringRecord, WriterBuilder};
-use std::io::Read;
+use std::collections::HashMap;
+use std::io::{Read, Write};
 
 const REQUIRED_FIELDS: &[&str] = &[
     "TX_ID", "TX_TYPE", "FROM_USER_ID", "TO_USER_ID",
     "AMOUNT", "TIMESTAMP", "STATUS", "DESCRIPTION"
 ];
+/// Optional last column, amounts are in the default currency without it
+const CURRENCY_FIELD: &str = "CURRENCY";
+/// Number of fields, including the optional currency
+const FIELD_COUNT: usize = 9;
+
+/// Dialect of CSV data
+///
+/// The default dialect is YPBankCsv itself: comma delimited, double quoted,
+/// with a header line of canonical field names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvDialect {
+    /// Field delimiter
+    pub delimiter: u8,
+    /// Quote character
+    pub quote: u8,
+    /// Alternative header names, mapped to canonical field names, e.g. `"Сумма" -> "AMOUNT"`
+    pub header_aliases: HashMap<String, String>,
+    /// Column names of data without a header line, in their order.
+    /// `None` means the first line is a header, by which the columns are found.
+    pub columns: Option<Vec<String>>,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            header_aliases: HashMap::new(),
+            columns: None,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// # Returning value
+    ///
+    /// Returns `Vec<String>` - all field names in the YPBankCsv order, including the currency
+    pub fn default_columns() -> Vec<String> {
+        YPBankCsvParser::expected_fields(true)
+    }
+
+    /// Resolves columns by their names
+    ///
+    /// # Parameters
+    ///
+    /// * `names` — column names in their order, canonical or aliases
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Columns, ParseError>`:
+    /// - `Ok(Columns)` — position of every field
+    /// - `Err(ParseError)` — unknown or duplicate column, or required column is missing
+    fn resolve_columns(&self, names: &[String]) -> Result<Columns, ParseError> {
+        let invalid = || {
+            ParseError::new(ParseErrorKind::InvalidHeader(names.to_vec(), YPBankCsvParser::expected_fields(false)))
+        };
+        let mut positions = [None; FIELD_COUNT];
+        let mut fields = Vec::with_capacity(names.len());
+        for (position, name) in names.iter().enumerate() {
+            let name = name.trim();
+            let field = self.header_aliases.get(name).map(String::as_str).unwrap_or(name);
+            let index = YPBankCsvParser::expected_fields(true)
+                .iter()
+                .position(|expected| expected == field)
+                .ok_or_else(invalid)?;
+            if positions[index].replace(position).is_some() {
+                return Err(invalid());
+            }
+            fields.push(field.to_string());
+        }
+        if positions[..usize::from(Currency)].iter().any(Option::is_none) {
+            return Err(invalid());
+        }
+        Ok(Columns { positions, fields })
+    }
+}
+
+impl TransactionFormat for CsvDialect {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        Box::new(YPBankCsvReader::with_dialect(reader, self.clone()))
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        Box::new(YPBankCsvReader::with_dialect(reader, self.clone()).with_default_currency(options.default_currency))
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(YPBankCsvWriter::with_dialect(writer, self.clone()))
+    }
+
+    fn writer_with_options<'a>(
+        &self,
+        writer: Box<dyn Write + 'a>,
+        options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(YPBankCsvWriter::with_dialect(writer, self.clone()).with_options(options))
+    }
+}
+
+/// Positions of fields in a CSV record
+#[derive(Debug, Clone)]
+struct Columns {
+    /// Column position of every field, indexed by `Idx`
+    positions: [Option<usize>; FIELD_COUNT],
+    /// Canonical field names of the columns in their order
+    fields: Vec<String>,
+}
+
+impl Columns {
+    /// Returns value of the field in the record
+    ///
+    /// Must be called only for the fields, resolved by `CsvDialect::resolve_columns`.
+    fn get<'r>(&self, record: &'r StringRecord, idx: Idx) -> &'r str {
+        self.positions[usize::from(idx)].map(|position| &record[position]).unwrap_or_default()
+    }
+
+    /// Checks if the currency column is present
+    fn has_currency(&self) -> bool {
+        self.positions[usize::from(Currency)].is_some()
+    }
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        let fields = YPBankCsvParser::expected_fields(false);
+        let mut positions = [None; FIELD_COUNT];
+        for (position, slot) in positions.iter_mut().enumerate().take(fields.len()) {
+            *slot = Some(position);
+        }
+        Self { positions, fields }
+    }
+}
 
 
 #[derive(Debug, Clone, Copy)]
@@ -23,7 +166,8 @@ enum Idx {
     Amount,
     Timestamp,
     Status,
-    Description
+    Description,
+    Currency
 }
 
 impl From<Idx> for usize {
@@ -37,6 +181,7 @@ impl From<Idx> for usize {
             Timestamp => 5,
             Status => 6,
             Description => 7,
+            Currency => 8,
         }
     }
 }
@@ -59,33 +204,44 @@ impl YPBankCsvParser {
     /// - `Ok(Vec<Transaction>)` — successful parsing, contains transaction vector
     /// - `Err(ParseError)` — parsing error (I/O, CSV, validation etc.)
     pub fn parse<R: Read>(reader: R) -> Result<Vec<Transaction>, ParseError> {
-        let mut csv_reader = ReaderBuilder::new()
-            .has_headers(true)
-            .trim(csv::Trim::All)
-            .flexible(false)
-            .quoting(true)
-            .from_reader(reader);
-        let mut transactions = Vec::new();
-        let headers = csv_reader.headers()?;
-        if let Err(e) = Self::validate_headers(headers) {
-            Err(e)
-        } else {
-            for (line_num, result) in csv_reader.into_records().enumerate() {
-                let record = result?;
-                if record.is_empty() || record.iter().all(|field| field.trim().is_empty()) {
-                    continue;
-                }
-                let transaction = Self::parse_record(&record, line_num + 2)?;
-                transactions.push(transaction);
-            }
-            Ok(transactions)
-        }
+        Self::iter(reader).collect()
+    }
+
+    /// Creates a streaming reader over data in YPBankCsv format.
+    ///
+    /// Headers are validated before the first record is returned,
+    /// records are parsed lazily one line at a time.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which CSV data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankCsvReader<R>` - iterator over parsed transactions
+    pub fn iter<R: Read>(reader: R) -> YPBankCsvReader<R> {
+        YPBankCsvReader::new(reader)
+    }
+
+    /// Creates a streaming reader over CSV data in the chosen dialect.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which CSV data can be read
+    /// * `dialect` — delimiter, quote, header names and column order of the data
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankCsvReader<R>` - iterator over parsed transactions
+    pub fn iter_with_dialect<R: Read>(reader: R, dialect: CsvDialect) -> YPBankCsvReader<R> {
+        YPBankCsvReader::with_dialect(reader, dialect)
     }
     
     /// Writes transaction vector into chosen sink in YPBankCsv format.
     ///
     /// Function serializes every transaction into CSV record and writes it into `writer`.
-    /// Also writes header, defined in `REQUIRED_FIELDS`.
+    /// Also writes header, defined in `REQUIRED_FIELDS`, with `CURRENCY` column,
+    /// if any amount is not in `DEFAULT_CURRENCY`.
     ///
     /// # Parameters
     ///
@@ -97,56 +253,95 @@ impl YPBankCsvParser {
     /// Returns `Result<(), ParseError>`:
     /// - `Ok(())` — all transactions successfully written
     /// - `Err(ParseError)` — write or validation error
-    pub fn write<W: std::io::Write>(transactions: &[Transaction], writer: &mut W,) -> Result<(), ParseError> {
-        let mut csv_writer = WriterBuilder::new()
-            .has_headers(false)
-            .flexible(false)
-            .quote_style(csv::QuoteStyle::Necessary)
-            .from_writer(writer);
-        csv_writer.write_record(REQUIRED_FIELDS)?;
+    pub fn write<W: Write>(transactions: &[Transaction], writer: &mut W,) -> Result<(), ParseError> {
+        let options = WriteOptions::for_transactions(transactions);
+        let mut csv_writer = Self::writer(writer).with_options(&options);
         for transaction in transactions {
-            if transaction.amount <= 0 {
-                return Err(ParseError::InvalidAmount(transaction.amount))
-            }
-            csv_writer.serialize(transaction)?
+            csv_writer.write_one(transaction)?;
         }
-        csv_writer.flush()?;
-        Ok(())
+        csv_writer.finish()
     }
 
-    /// Checks if csv headers are valid
-    fn validate_headers(headers: &StringRecord) -> Result<(), ParseError> {
-        let required_fields: Vec<String> = REQUIRED_FIELDS.iter().map(|s| s.to_string()).collect();
-        let actual_fields: Vec<String> = headers.iter().map(|s| s.trim().to_string()).collect();
-        if actual_fields.len() != required_fields.len() {
-            return Err(ParseError::InvalidHeader(actual_fields, required_fields));
-        }
-        for (expected, actual) in required_fields.iter().zip(actual_fields.iter()) {
-            if expected != actual {
-                return Err(ParseError::InvalidHeader(actual_fields, required_fields));
-            }
+    /// Creates an incremental writer of transactions in YPBankCsv format.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which CSV data will be written
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankCsvWriter<W>` - writer, serializing transactions one at a time
+    pub fn writer<W: Write>(writer: W) -> YPBankCsvWriter<W> {
+        YPBankCsvWriter::new(writer)
+    }
+
+    /// Creates an incremental writer of transactions as CSV data in the chosen dialect.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which CSV data will be written
+    /// * `dialect` — delimiter, quote and column order of the data
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankCsvWriter<W>` - writer, serializing transactions one at a time
+    pub fn writer_with_dialect<W: Write>(writer: W, dialect: CsvDialect) -> YPBankCsvWriter<W> {
+        YPBankCsvWriter::with_dialect(writer, dialect)
+    }
+
+    /// Returns field names of a record with or without the currency column
+    fn expected_fields(has_currency: bool) -> Vec<String> {
+        let mut fields: Vec<String> = REQUIRED_FIELDS.iter().map(|s| s.to_string()).collect();
+        if has_currency {
+            fields.push(CURRENCY_FIELD.to_string());
         }
-        Ok(())
+        fields
+    }
+
+    /// Finds columns by the header line
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Columns, ParseError>`:
+    /// - `Ok(Columns)` — headers are valid
+    /// - `Err(ParseError)` — invalid headers
+    fn validate_headers(headers: &StringRecord, dialect: &CsvDialect) -> Result<Columns, ParseError> {
+        let actual_fields: Vec<String> = headers.iter().map(|s| s.trim().to_string()).collect();
+        dialect.resolve_columns(&actual_fields).map_err(|e| {
+            e.with_location(Location {
+                line: headers.position().map(|p| p.line() as usize),
+                byte_offset: headers.position().map(|p| p.byte()),
+                ..Location::default()
+            })
+        })
     }
 
     /// Parses a single record to return a transaction
-    fn parse_record(record: &StringRecord, line_num: usize) -> Result<Transaction, ParseError> {
-        let required_fields: Vec<String> = REQUIRED_FIELDS.iter().map(|s| s.to_string()).collect();
-        let actual_fields: Vec<String> = record.iter().map(|s| s.trim().to_string()).collect();
-        if record.len() != required_fields.len() {
-            return Err(ParseError::InvalidRecord(actual_fields, required_fields));
-        }
-        let tx_id = parse_number("TxId", &record[TxId.into()], line_num)?;
-        let tx_type = parse_transaction_type(&record[TxType.into()], line_num)?;
-        let from_user_id = parse_number("FromUserId", &record[FromUserId.into()], line_num)?;
-        let to_user_id = parse_number("ToUserId", &record[ToUserId.into()], line_num)?;
-        let amount = parse_number("AMOUNT", &record[Amount.into()], line_num)?;
-        if amount <= 0 {
-            return Err(ParseError::InvalidAmount(amount))
-        }
-        let timestamp = parse_number("TIMESTAMP", &record[Timestamp.into()], line_num)?;
-        let status = parse_transaction_status(&record[Status.into()], line_num)?;
-        let description = record[Description.into()].to_string();
+    fn parse_record(
+        record: &StringRecord,
+        location: &Location,
+        columns: &Columns,
+        default_currency: CurrencyCode,
+    ) -> Result<Transaction, ParseError> {
+        if record.len() != columns.fields.len() {
+            let actual_fields: Vec<String> = record.iter().map(|s| s.trim().to_string()).collect();
+            return Err(ParseError::new(ParseErrorKind::InvalidRecord(actual_fields, columns.fields.clone()))
+                .with_location(location.clone()));
+        }
+        let tx_id = parse_number("TX_ID", columns.get(record, TxId), location)?;
+        let tx_type = parse_transaction_type(columns.get(record, TxType), location)?;
+        let from_user_id = parse_number("FROM_USER_ID", columns.get(record, FromUserId), location)?;
+        let to_user_id = parse_number("TO_USER_ID", columns.get(record, ToUserId), location)?;
+        let amount = parse_number("AMOUNT", columns.get(record, Amount), location)?;
+        let currency = if columns.has_currency() {
+            parse_currency(columns.get(record, Currency), location)?
+        } else {
+            default_currency
+        };
+        let amount = Money::new(amount, currency);
+        let timestamp = parse_number("TIMESTAMP", columns.get(record, Timestamp), location)?;
+        let status = parse_transaction_status(columns.get(record, Status), location)?;
+        let description = columns.get(record, Description).to_string();
         let transaction = Transaction {
             tx_id,
             tx_type,
@@ -157,7 +352,308 @@ impl YPBankCsvParser {
             status,
             description
         };
-        transaction.validate()?;
+        transaction.validate().map_err(|e| e.with_location(location.clone()))?;
         Ok(transaction)
     }
-}
\ No newline at end of file
+}
+
+impl TransactionFormat for YPBankCsvParser {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        Box::new(YPBankCsvParser::iter(reader))
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        Box::new(YPBankCsvParser::iter(reader).with_default_currency(options.default_currency))
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(YPBankCsvParser::writer(writer))
+    }
+
+    fn writer_with_options<'a>(
+        &self,
+        writer: Box<dyn Write + 'a>,
+        options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(YPBankCsvParser::writer(writer).with_options(options))
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        let head = String::from_utf8_lossy(head);
+        let Some(first_line) = head.trim_start_matches('\u{feff}').lines().find(|line| !line.trim().is_empty()) else {
+            return false;
+        };
+        let mut fields: Vec<&str> = first_line.split(',').map(|field| field.trim().trim_matches('"')).collect();
+        if fields.last() == Some(&CURRENCY_FIELD) {
+            fields.pop();
+        }
+        fields == REQUIRED_FIELDS
+    }
+}
+
+/// Incremental writer of transactions in YPBankCsv format
+///
+/// Header, defined in `REQUIRED_FIELDS`, is written exactly once:
+/// before the first record or on `finish` if no records were written.
+/// `CURRENCY` column is added, if it is chosen with `with_currency_column`
+/// or the first record is not in `DEFAULT_CURRENCY`.
+/// Dialects without a header line write records in the order of their columns.
+pub struct YPBankCsvWriter<W: Write> {
+    csv_writer: csv::Writer<W>,
+    dialect: CsvDialect,
+    currency_column: bool,
+    columns: Option<Columns>,
+}
+
+impl<W: Write> YPBankCsvWriter<W> {
+    /// Creates a new writer
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which CSV data will be written
+    pub fn new(writer: W) -> Self {
+        Self::with_dialect(writer, CsvDialect::default())
+    }
+
+    /// Creates a new writer of CSV data in the chosen dialect
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which CSV data will be written
+    /// * `dialect` — delimiter, quote and column order of the data
+    pub fn with_dialect(writer: W, dialect: CsvDialect) -> Self {
+        let csv_writer = WriterBuilder::new()
+            .has_headers(false)
+            .flexible(false)
+            .delimiter(dialect.delimiter)
+            .quote(dialect.quote)
+            .quote_style(csv::QuoteStyle::Necessary)
+            .from_writer(writer);
+        Self {
+            csv_writer,
+            dialect,
+            currency_column: false,
+            columns: None,
+        }
+    }
+
+    /// Adds `CURRENCY` column to the header regardless of the currencies of the records
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankCsvWriter<W>` - the writer with the currency column
+    pub fn with_currency_column(mut self) -> Self {
+        self.currency_column = true;
+        self
+    }
+
+    /// Applies the writing settings
+    ///
+    /// # Parameters
+    ///
+    /// * `options` — writing settings, `currencies` adds `CURRENCY` column
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankCsvWriter<W>` - the writer with the settings
+    pub fn with_options(mut self, options: &WriteOptions) -> Self {
+        self.currency_column |= options.currencies;
+        self
+    }
+
+    /// Writes header if it has not been written yet
+    ///
+    /// # Parameters
+    ///
+    /// * `needs_currency` — whether the first record can not be written without the currency
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<&Columns, ParseError>` - order of the written columns
+    fn ensure_header(&mut self, needs_currency: bool) -> Result<&Columns, ParseError> {
+        let columns = match (self.columns.take(), &self.dialect.columns) {
+            (Some(columns), _) => columns,
+            (None, Some(names)) => self.dialect.resolve_columns(names)?,
+            (None, None) => {
+                let fields = YPBankCsvParser::expected_fields(self.currency_column || needs_currency);
+                self.csv_writer.write_record(&fields)?;
+                CsvDialect::default().resolve_columns(&fields)?
+            }
+        };
+        Ok(self.columns.insert(columns))
+    }
+}
+
+impl<W: Write> TransactionWriter for YPBankCsvWriter<W> {
+    fn write_one(&mut self, transaction: &Transaction) -> Result<(), ParseError> {
+        check_amount(transaction)?;
+        let amount = currency_minor_units(&transaction.amount)?;
+        let needs_currency = transaction.amount.currency != DEFAULT_CURRENCY;
+        let columns = self.ensure_header(needs_currency)?;
+        if !columns.has_currency() && needs_currency {
+            return Err(ParseErrorKind::Validation(format!(
+                "amount in {} can not be written without the {} column, \
+                which is added for the first record or by WriteOptions::currencies",
+                transaction.amount.currency, CURRENCY_FIELD
+            ))
+            .into());
+        }
+        let values = [
+            transaction.tx_id.to_string(),
+            transaction.tx_type.to_string(),
+            transaction.from_user_id.to_string(),
+            transaction.to_user_id.to_string(),
+            amount.to_string(),
+            transaction.timestamp.to_string(),
+            transaction.status.to_string(),
+            transaction.description.clone(),
+            transaction.amount.currency.to_string(),
+        ];
+        let mut record = vec![String::new(); columns.fields.len()];
+        for (value, position) in values.into_iter().zip(columns.positions) {
+            if let Some(position) = position {
+                record[position] = value;
+            }
+        }
+        self.csv_writer.write_record(&record)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.ensure_header(false)?;
+        self.csv_writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Streaming reader of transactions in YPBankCsv format
+///
+/// Validates headers on the first call to `next` and then yields
+/// a transaction for every CSV record. An invalid record is reported as an error
+/// and skipped, iteration stops after invalid headers or an I/O error.
+pub struct YPBankCsvReader<R: Read> {
+    csv_reader: csv::Reader<R>,
+    dialect: CsvDialect,
+    record: StringRecord,
+    record_index: usize,
+    headers_checked: bool,
+    columns: Columns,
+    default_currency: CurrencyCode,
+    finished: bool,
+}
+
+impl<R: Read> YPBankCsvReader<R> {
+    /// Creates a new reader
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which CSV data can be read
+    pub fn new(reader: R) -> Self {
+        Self::with_dialect(reader, CsvDialect::default())
+    }
+
+    /// Creates a new reader of CSV data in the chosen dialect
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which CSV data can be read
+    /// * `dialect` — delimiter, quote, header names and column order of the data
+    pub fn with_dialect(reader: R, dialect: CsvDialect) -> Self {
+        let csv_reader = ReaderBuilder::new()
+            .has_headers(dialect.columns.is_none())
+            .trim(csv::Trim::All)
+            .flexible(false)
+            .quoting(true)
+            .delimiter(dialect.delimiter)
+            .quote(dialect.quote)
+            .from_reader(reader);
+        Self {
+            csv_reader,
+            dialect,
+            record: StringRecord::new(),
+            record_index: 0,
+            headers_checked: false,
+            columns: Columns::default(),
+            default_currency: DEFAULT_CURRENCY,
+            finished: false,
+        }
+    }
+
+    /// Chooses currency of records, when the data has no `CURRENCY` column
+    ///
+    /// # Parameters
+    ///
+    /// * `currency` — currency of amounts, `DEFAULT_CURRENCY` unless chosen
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankCsvReader<R>` - the reader with the default currency
+    pub fn with_default_currency(mut self, currency: CurrencyCode) -> Self {
+        self.default_currency = currency;
+        self
+    }
+
+    /// Finds columns by the header line or by the dialect, if the data has no header
+    fn read_columns(&mut self) -> Result<Columns, ParseError> {
+        match &self.dialect.columns {
+            Some(names) => self.dialect.resolve_columns(names),
+            None => YPBankCsvParser::validate_headers(self.csv_reader.headers()?, &self.dialect),
+        }
+    }
+
+    /// Reads the next non-empty CSV record and parses it
+    fn read_record(&mut self) -> Result<Option<Transaction>, ParseError> {
+        while self.csv_reader.read_record(&mut self.record)? {
+            if self.record.is_empty() || self.record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            let location = Location {
+                line: self.record.position().map(|p| p.line() as usize),
+                byte_offset: self.record.position().map(|p| p.byte()),
+                ..Location::default()
+            };
+            return YPBankCsvParser::parse_record(&self.record, &location, &self.columns, self.default_currency).map(Some);
+        }
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for YPBankCsvReader<R> {
+    type Item = Result<Transaction, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if !self.headers_checked {
+            self.headers_checked = true;
+            match self.read_columns() {
+                Ok(columns) => self.columns = columns,
+                Err(e) => {
+                    self.finished = !e.is_recoverable();
+                    return Some(Err(e));
+                }
+            }
+        }
+        let record_index = self.record_index;
+        self.record_index += 1;
+        match self.read_record() {
+            Ok(Some(transaction)) => Some(Ok(transaction)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = !e.is_recoverable();
+                Some(Err(e.with_location(Location {
+                    record_index: Some(record_index),
+                    ..Location::default()
+                })))
+            }
+        }
+    }
+}
@@ -1,10 +1,24 @@
-use crate::errors::ParseError;
-use crate::format::common::{parse_description, parse_number, parse_transaction_status, parse_transaction_type};
+use crate::errors::{Location, ParseError, ParseErrorKind};
+use crate::format::{Chunk, ReadOptions, TransactionFormat, TransactionIter, TransactionWriter};
+use crate::format::common::{
+    currency_minor_units, escape_description, parse_currency, parse_description, parse_number, parse_transaction_status,
+    parse_transaction_type, split_at_records,
+};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use crate::model::money::Money;
 use crate::model::transaction::Transaction;
 use crate::model::transaction_status::TransactionStatus;
 use crate::model::transaction_type::TransactionType;
+use crate::validation::check_amount;
 use std::collections::HashMap;
-use std::io::{BufRead, Read};
+use std::io::{BufRead, BufReader, Lines, Read, Write};
+
+const REQUIRED_FIELDS: &[&str] = &[
+    "TX_ID", "TX_TYPE", "FROM_USER_ID", "TO_USER_ID",
+    "AMOUNT", "TIMESTAMP", "STATUS", "DESCRIPTION"
+];
+/// Optional field, amounts are in the default currency without it
+const CURRENCY_FIELD: &str = "CURRENCY";
 
 /// Reading and writing data in YPBankText format
 pub struct YPBankTextParser;
@@ -24,47 +38,23 @@ impl YPBankTextParser {
     /// - `Ok(Vec<Transaction>)` — successful parsing, contains transaction vector
     /// - `Err(ParseError)` — parsing error (I/O, CSV, validation etc.)
     pub fn parse<R: Read>(reader: R) -> Result<Vec<Transaction>, ParseError> {
-        let reader = std::io::BufReader::new(reader);
-        let mut transactions = Vec::new();
-        let mut current_record = HashMap::new();
-        let mut line_number = 0;
-        for line in reader.lines() {
-            line_number += 1;
-            let line = line?;
-            let line = line.trim();
-            if line.is_empty() {
-                if !current_record.is_empty() {
-                    let transaction = Self::parse_record(&current_record, line_number)?;
-                    transactions.push(transaction);
-                    current_record.clear();
-                }
-                continue;
-            }
-            if line.starts_with('#') {
-                continue;
-            }
-            let parts: Vec<&str> = line.splitn(2, ':').collect();
-            if parts.len() != 2 {
-                return Err(ParseError::Validation(format!(
-                    "Invalid line format at line {}: '{}'",
-                    line_number, line
-                )));
-            }
-            let key = parts[0].trim();
-            let value = parts[1].trim();
-            if current_record.contains_key(key) {
-                return Err(ParseError::Validation(format!(
-                    "Duplicate key '{}' in transaction at line {}",
-                    key, line_number
-                )));
-            }
-            current_record.insert(key.to_string(), value.to_string());
-        }
-        if !current_record.is_empty() {
-            let transaction = Self::parse_record(&current_record, line_number)?;
-            transactions.push(transaction);
-        }
-        Ok(transactions)
+        Self::iter(reader).collect()
+    }
+
+    /// Creates a streaming reader over data in YPBankText format.
+    ///
+    /// Records are parsed lazily one text block at a time, so the whole
+    /// input never has to be kept in memory.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which text data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankTextReader<R>` - iterator over parsed transactions
+    pub fn iter<R: Read>(reader: R) -> YPBankTextReader<R> {
+        YPBankTextReader::new(reader)
     }
 
     /// Writes transaction vector into chosen sink in YPBankBin format.
@@ -81,106 +71,65 @@ impl YPBankTextParser {
     /// Returns `Result<(), ParseError>`:
     /// - `Ok(())` — all transactions successfully written
     /// - `Err(ParseError)` — write or validation error
-    pub fn write<W: std::io::Write>(
+    pub fn write<W: Write>(
         transactions: &[Transaction],
         writer: &mut W,
         include_comments: bool,
     ) -> Result<(), ParseError> {
-        for (i, transaction) in transactions.iter().enumerate() {
-            if include_comments {
-                writeln!(writer, "# Record {} ({}): {}",
-                         i + 1,
-                         match transaction.tx_type {
-                             TransactionType::Deposit => "Deposit",
-                             TransactionType::Transfer => "Transfer",
-                             TransactionType::Withdrawal => "Withdrawal",
-                         },
-                         transaction.description
-                )?;
-            }
-            writeln!(writer, "TX_ID: {}", transaction.tx_id)?;
-            writeln!(writer, "TX_TYPE: {}", match transaction.tx_type {
-                TransactionType::Deposit => "DEPOSIT",
-                TransactionType::Transfer => "TRANSFER",
-                TransactionType::Withdrawal => "WITHDRAWAL",
-            })?;
-            writeln!(writer, "FROM_USER_ID: {}", transaction.from_user_id)?;
-            writeln!(writer, "TO_USER_ID: {}", transaction.to_user_id)?;
-            if transaction.amount <= 0 {
-                return Err(ParseError::InvalidAmount(transaction.amount))
-            }
-            writeln!(writer, "AMOUNT: {}", transaction.amount)?;
-            writeln!(writer, "TIMESTAMP: {}", transaction.timestamp)?;
-            writeln!(writer, "STATUS: {}", match transaction.status {
-                TransactionStatus::Success => "SUCCESS",
-                TransactionStatus::Failure => "FAILURE",
-                TransactionStatus::Pending => "PENDING",
-            })?;
-            writeln!(writer, "DESCRIPTION: \"{}\"", transaction.description)?;
-            if i < transactions.len() - 1 {
-                writeln!(writer)?;
-            }
+        let mut text_writer = Self::writer(writer, include_comments);
+        for transaction in transactions {
+            text_writer.write_one(transaction)?;
         }
-        Ok(())
+        text_writer.finish()
+    }
+
+    /// Creates an incremental writer of transactions in YPBankText format.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which text data will be written
+    /// * `include_comments` — whether to precede every record with a `# Record` comment line
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankTextWriter<W>` - writer, serializing transactions one at a time
+    pub fn writer<W: Write>(writer: W, include_comments: bool) -> YPBankTextWriter<W> {
+        YPBankTextWriter::new(writer, include_comments)
     }
 
     /// Parses a single record to return a transaction
     fn parse_record(
-        record: &HashMap<String, String>,
+        record: &HashMap<String, TextField>,
         line_number: usize,
+        strict: bool,
+        default_currency: CurrencyCode,
     ) -> Result<Transaction, ParseError> {
-        let required_fields = [
-            "TX_ID", "TX_TYPE", "FROM_USER_ID", "TO_USER_ID",
-            "AMOUNT", "TIMESTAMP", "STATUS", "DESCRIPTION"
-        ];
-        for &field in &required_fields {
+        for &field in REQUIRED_FIELDS {
             if !record.contains_key(field) {
-                return Err(ParseError::Validation(format!(
-                    "Missing required field '{}' in transaction ending at line {}",
-                    field, line_number
-                )));
+                return Err(Self::make_error(field, line_number));
             }
         }
-        let tx_id = parse_number(
-            "TX_ID",
-            record.get("TX_ID").ok_or_else(|| Self::make_error("TX_ID", line_number))?,
-            line_number
-        )?;
-        let tx_type = parse_transaction_type(
-            record.get("TX_TYPE").ok_or_else(|| Self::make_error("TX_TYPE", line_number))?,
-            line_number
-        )?;
-        let from_user_id = parse_number(
-            "FROM_USER_ID",
-            record.get("FROM_USER_ID").ok_or_else(|| Self::make_error("FROM_USER_ID", line_number))?,
-            line_number
-        )?;
-        let to_user_id = parse_number(
-            "TO_USER_ID",
-            record.get("TO_USER_ID").ok_or_else(|| Self::make_error("TO_USER_ID", line_number))?,
-            line_number
-        )?;
-        let amount = parse_number(
-            "AMOUNT",
-            record.get("AMOUNT").ok_or_else(|| Self::make_error("AMOUNT", line_number))?,
-            line_number
-        )?;
-        if amount <= 0 {
-            return Err(ParseError::InvalidAmount(amount))
-        }
-        let timestamp = parse_number(
-            "TIMESTAMP",
-            record.get("TIMESTAMP").ok_or_else(|| Self::make_error("TIMESTAMP", line_number))?,
-            line_number
-        )?;
-        let status = parse_transaction_status(
-            record.get("STATUS").ok_or_else(|| Self::make_error("STATUS", line_number))?,
-            line_number
-        )?;
-        let description = parse_description(
-            record.get("DESCRIPTION").ok_or_else(|| Self::make_error("DESCRIPTION", line_number))?,
-            line_number
-        )?;
+        let field = Self::get_field(record, "TX_ID", line_number)?;
+        let tx_id = parse_number("TX_ID", &field.value, &field.location)?;
+        let field = Self::get_field(record, "TX_TYPE", line_number)?;
+        let tx_type = parse_transaction_type(&field.value, &field.location)?;
+        let field = Self::get_field(record, "FROM_USER_ID", line_number)?;
+        let from_user_id = parse_number("FROM_USER_ID", &field.value, &field.location)?;
+        let field = Self::get_field(record, "TO_USER_ID", line_number)?;
+        let to_user_id = parse_number("TO_USER_ID", &field.value, &field.location)?;
+        let field = Self::get_field(record, "AMOUNT", line_number)?;
+        let amount = parse_number("AMOUNT", &field.value, &field.location)?;
+        let currency = match record.get(CURRENCY_FIELD) {
+            Some(field) => parse_currency(&field.value, &field.location)?,
+            None => default_currency,
+        };
+        let amount = Money::new(amount, currency);
+        let field = Self::get_field(record, "TIMESTAMP", line_number)?;
+        let timestamp = parse_number("TIMESTAMP", &field.value, &field.location)?;
+        let field = Self::get_field(record, "STATUS", line_number)?;
+        let status = parse_transaction_status(&field.value, &field.location)?;
+        let field = Self::get_field(record, "DESCRIPTION", line_number)?;
+        let description = parse_description(&field.value, &field.location, strict)?;
         let transaction = Transaction {
             tx_id,
             tx_type,
@@ -191,13 +140,305 @@ impl YPBankTextParser {
             status,
             description,
         };
-        transaction.validate()?;
+        transaction.validate().map_err(|e| {
+            let location = e.location().field.as_ref()
+                .and_then(|name| record.get(name))
+                .map(|field| field.location.clone())
+                .unwrap_or_default();
+            e.with_location(location)
+        })?;
         Ok(transaction)
     }
 
+    fn get_field<'r>(
+        record: &'r HashMap<String, TextField>,
+        field_name: &str,
+        line_number: usize,
+    ) -> Result<&'r TextField, ParseError> {
+        record.get(field_name).ok_or_else(|| Self::make_error(field_name, line_number))
+    }
+
     fn make_error(field_name: &str, line_number: usize) -> ParseError {
-        ParseError::InvalidData(
-            format!("Field '{}' is required but missing in line {}", field_name, line_number),
-        )
+        ParseError::new(ParseErrorKind::MissingField(field_name.to_string()))
+            .with_field(field_name)
+            .with_location(Location {
+                line: Some(line_number),
+                ..Location::default()
+            })
+    }
+}
+
+/// Field value of a text block together with its location
+struct TextField {
+    value: String,
+    location: Location,
+}
+
+impl TransactionFormat for YPBankTextParser {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+        Box::new(YPBankTextParser::iter(reader))
+    }
+
+    fn reader_with_options<'a>(&self, reader: Box<dyn Read + 'a>, options: &ReadOptions) -> TransactionIter<'a> {
+        let reader = YPBankTextParser::iter(reader).with_default_currency(options.default_currency);
+        if options.strict_descriptions {
+            Box::new(reader.with_strict_mode())
+        } else {
+            Box::new(reader)
+        }
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(YPBankTextParser::writer(writer, true))
+    }
+
+    fn detect(&self, head: &[u8]) -> bool {
+        let head = String::from_utf8_lossy(head);
+        let Some(first_line) = head
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+        else {
+            return false;
+        };
+        match first_line.split_once(':') {
+            Some((key, _)) => REQUIRED_FIELDS.contains(&key.trim()) || key.trim() == CURRENCY_FIELD,
+            None => false,
+        }
+    }
+
+    fn split<'d>(&self, data: &'d [u8], max_chunks: usize) -> Option<Vec<Chunk<'d>>> {
+        // every line after a blank one may start a record
+        let mut starts = vec![(0, 0)];
+        let mut position = 0;
+        let mut previous_blank = false;
+        for (line_number, line) in data.split_inclusive(|&byte| byte == b'\n').enumerate() {
+            let blank = line.iter().all(u8::is_ascii_whitespace);
+            if previous_blank && !blank {
+                starts.push((position, line_number));
+            }
+            previous_blank = blank;
+            position += line.len();
+        }
+        Some(split_at_records(data, &[], &starts, max_chunks))
+    }
+}
+
+/// Incremental writer of transactions in YPBankText format
+///
+/// Records are separated with an empty line, which is written
+/// before every record except the first one.
+pub struct YPBankTextWriter<W: Write> {
+    writer: W,
+    include_comments: bool,
+    records_written: usize,
+}
+
+impl<W: Write> YPBankTextWriter<W> {
+    /// Creates a new writer
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which text data will be written
+    /// * `include_comments` — whether to precede every record with a `# Record` comment line
+    pub fn new(writer: W, include_comments: bool) -> Self {
+        Self {
+            writer,
+            include_comments,
+            records_written: 0,
+        }
+    }
+}
+
+impl<W: Write> TransactionWriter for YPBankTextWriter<W> {
+    fn write_one(&mut self, transaction: &Transaction) -> Result<(), ParseError> {
+        check_amount(transaction)?;
+        let amount = currency_minor_units(&transaction.amount)?;
+        let writer = &mut self.writer;
+        if self.records_written > 0 {
+            writeln!(writer)?;
+        }
+        if self.include_comments {
+            writeln!(writer, "# Record {} ({}): {}",
+                     self.records_written + 1,
+                     match transaction.tx_type {
+                         TransactionType::Deposit => "Deposit",
+                         TransactionType::Transfer => "Transfer",
+                         TransactionType::Withdrawal => "Withdrawal",
+                     },
+                     escape_description(&transaction.description)
+            )?;
+        }
+        writeln!(writer, "TX_ID: {}", transaction.tx_id)?;
+        writeln!(writer, "TX_TYPE: {}", match transaction.tx_type {
+            TransactionType::Deposit => "DEPOSIT",
+            TransactionType::Transfer => "TRANSFER",
+            TransactionType::Withdrawal => "WITHDRAWAL",
+        })?;
+        writeln!(writer, "FROM_USER_ID: {}", transaction.from_user_id)?;
+        writeln!(writer, "TO_USER_ID: {}", transaction.to_user_id)?;
+        writeln!(writer, "AMOUNT: {}", amount)?;
+        writeln!(writer, "{}: {}", CURRENCY_FIELD, transaction.amount.currency)?;
+        writeln!(writer, "TIMESTAMP: {}", transaction.timestamp)?;
+        writeln!(writer, "STATUS: {}", match transaction.status {
+            TransactionStatus::Success => "SUCCESS",
+            TransactionStatus::Failure => "FAILURE",
+            TransactionStatus::Pending => "PENDING",
+        })?;
+        writeln!(writer, "DESCRIPTION: \"{}\"", escape_description(&transaction.description))?;
+        self.records_written += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Streaming reader of transactions in YPBankText format
+///
+/// Reads the source line by line and yields a transaction for every
+/// text block, separated by empty lines. An invalid block is reported as an error
+/// and skipped, iteration stops after the first I/O error.
+pub struct YPBankTextReader<R: Read> {
+    lines: Lines<BufReader<R>>,
+    line_number: usize,
+    record_index: usize,
+    strict: bool,
+    default_currency: CurrencyCode,
+    finished: bool,
+}
+
+impl<R: Read> YPBankTextReader<R> {
+    /// Creates a new reader
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which text data can be read
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: BufReader::new(reader).lines(),
+            line_number: 0,
+            record_index: 0,
+            strict: false,
+            default_currency: DEFAULT_CURRENCY,
+            finished: false,
+        }
+    }
+
+    /// Enables strict mode, in which descriptions with unknown escapes
+    /// or unescaped quotes are rejected instead of being kept as is
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankTextReader<R>` - the reader in strict mode
+    pub fn with_strict_mode(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Chooses currency of records without `CURRENCY` field
+    ///
+    /// # Parameters
+    ///
+    /// * `currency` — currency of amounts, `DEFAULT_CURRENCY` unless chosen
+    ///
+    /// # Returning value
+    ///
+    /// Returns `YPBankTextReader<R>` - the reader with the default currency
+    pub fn with_default_currency(mut self, currency: CurrencyCode) -> Self {
+        self.default_currency = currency;
+        self
+    }
+
+    /// Reads lines up to the end of the next text block and parses it
+    fn read_record(&mut self) -> Result<Option<Transaction>, ParseError> {
+        let mut current_record = HashMap::new();
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            let raw_line = line?;
+            let line = raw_line.trim();
+            if line.is_empty() {
+                if !current_record.is_empty() {
+                    return YPBankTextParser::parse_record(&current_record, self.line_number - 1, self.strict, self.default_currency).map(Some);
+                }
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+            let line_location = |offset: usize| Location {
+                line: Some(self.line_number),
+                column: Some(raw_line[..offset].chars().count() + 1),
+                ..Location::default()
+            };
+            let key_offset = raw_line.len() - raw_line.trim_start().len();
+            let Some((key, value)) = line.split_once(':') else {
+                let error = ParseError::new(ParseErrorKind::InvalidLineFormat(line.to_string()))
+                    .with_location(line_location(key_offset));
+                self.skip_record()?;
+                return Err(error);
+            };
+            let value_offset = key_offset + key.len() + 1 + (value.len() - value.trim_start().len());
+            let key = key.trim();
+            let value = value.trim();
+            if current_record.contains_key(key) {
+                let error = ParseError::new(ParseErrorKind::DuplicateField(key.to_string()))
+                    .with_field(key)
+                    .with_location(line_location(key_offset));
+                self.skip_record()?;
+                return Err(error);
+            }
+            let location = line_location(value_offset);
+            current_record.insert(key.to_string(), TextField { value: value.to_string(), location });
+        }
+        if current_record.is_empty() {
+            Ok(None)
+        } else {
+            YPBankTextParser::parse_record(&current_record, self.line_number, self.strict, self.default_currency).map(Some)
+        }
+    }
+
+    /// Skips the rest of the current text block after an invalid line
+    fn skip_record(&mut self) -> Result<(), ParseError> {
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            if line?.trim().is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for YPBankTextReader<R> {
+    type Item = Result<Transaction, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let record_index = self.record_index;
+        self.record_index += 1;
+        match self.read_record() {
+            Ok(Some(transaction)) => Some(Ok(transaction)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = !e.is_recoverable();
+                Some(Err(e.with_location(Location {
+                    record_index: Some(record_index),
+                    ..Location::default()
+                })))
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -25,3 +25,27 @@ pub mod model;
 /// This module is responsible for dispatching reading and writing to different parsers
 /// depending on the chosen format
 pub mod parser;
+
+/// # Diagnostics module
+///
+/// This module renders parse errors against the original input,
+/// pointing at the exact spot, where the error occurred
+pub mod diagnostics;
+
+/// # Reconciliation module
+///
+/// This module matches transactions of two inputs by their IDs
+/// and reports missing and differing transactions
+pub mod reconciliation;
+
+/// # Compression module
+///
+/// This module detects compressed inputs by their "magic" bytes
+/// and compresses outputs with gzip or zstd
+pub mod compression;
+
+/// # Validation module
+///
+/// This module contains transaction validation rules
+/// and the validator, combining them with their severities
+pub mod validation;
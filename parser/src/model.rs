@@ -2,6 +2,10 @@
 ///
 /// This module contains Transaction entity
 pub mod transaction;
+/// # Transaction view module
+///
+/// This module contains borrowed Transaction view
+pub mod transaction_ref;
 /// # Transaction type module
 ///
 /// This module contains Transaction type entity
@@ -10,3 +14,11 @@ pub mod transaction_type;
 ///
 /// This module contains Transaction status entity
 pub mod transaction_status;
+/// # Currency module
+///
+/// This module contains ISO 4217 currency codes
+pub mod currency;
+/// # Money module
+///
+/// This module contains Money entity
+pub mod money;
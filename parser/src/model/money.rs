@@ -0,0 +1,143 @@
+use crate::errors::{ParseError, ParseErrorKind};
+use crate::model::currency::{CurrencyCode, DEFAULT_CURRENCY};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+/// Maximum number of digits after the decimal separator, `10^18` still fits into `i64`
+pub const MAX_SCALE: u8 = 18;
+
+/// Amount of money in a currency
+///
+/// Amount is stored as an integer number of minor units, e.g. `12345`
+/// with scale `2` means `123.45`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Money {
+    /// Amount in minor units
+    pub minor_units: i64,
+    /// Currency
+    pub currency: CurrencyCode,
+    /// Number of digits after the decimal separator
+    pub scale: u8,
+}
+
+impl Money {
+    /// Creates money with the minor unit of the currency as a scale
+    ///
+    /// # Parameters
+    ///
+    /// * `minor_units` — amount in minor units of the currency, e.g. cents for `USD`
+    /// * `currency` — currency
+    pub fn new(minor_units: i64, currency: CurrencyCode) -> Self {
+        Self { minor_units, currency, scale: currency.minor_unit() }
+    }
+
+    /// Creates money with an explicit scale
+    ///
+    /// # Parameters
+    ///
+    /// * `minor_units` — amount in units of `10^-scale`
+    /// * `currency` — currency
+    /// * `scale` — number of digits after the decimal separator
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Money, ParseError>`:
+    /// - `Ok(Money)` — money with the scale
+    /// - `Err(ParseError)` — scale exceeds `MAX_SCALE`
+    pub fn with_scale(minor_units: i64, currency: CurrencyCode, scale: u8) -> Result<Self, ParseError> {
+        if scale > MAX_SCALE {
+            return Err(ParseErrorKind::InvalidData(format!(
+                "Scale {} exceeds the maximum {}",
+                scale, MAX_SCALE
+            )).into());
+        }
+        Ok(Self { minor_units, currency, scale })
+    }
+
+    /// Converts money to another scale without loss of precision
+    ///
+    /// # Parameters
+    ///
+    /// * `scale` — required number of digits after the decimal separator
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<Money>`:
+    /// - `Some(Money)` — the same amount with the required scale
+    /// - `None` — amount can not be represented exactly or overflows
+    pub fn rescale(&self, scale: u8) -> Option<Self> {
+        let minor_units = if scale >= self.scale {
+            let factor = 10i64.checked_pow(u32::from(scale - self.scale))?;
+            self.minor_units.checked_mul(factor)?
+        } else {
+            let factor = 10i64.checked_pow(u32::from(self.scale - scale))?;
+            if self.minor_units % factor != 0 {
+                return None;
+            }
+            self.minor_units / factor
+        };
+        Self::with_scale(minor_units, self.currency, scale).ok()
+    }
+
+    /// Converts money to the minor unit of its currency without loss of precision
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<i64>`:
+    /// - `Some(i64)` — amount in minor units of the currency
+    /// - `None` — amount can not be represented exactly or overflows
+    pub fn currency_minor_units(&self) -> Option<i64> {
+        self.rescale(self.currency.minor_unit()).map(|money| money.minor_units)
+    }
+}
+
+impl fmt::Display for Money {
+    /// Money formatted representation, e.g. `123.45 USD`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.minor_units < 0 { "-" } else { "" };
+        let units = self.minor_units.unsigned_abs();
+        if self.scale == 0 {
+            return write!(f, "{}{} {}", sign, units, self.currency);
+        }
+        let Some(divisor) = 10u128.checked_pow(u32::from(self.scale)) else {
+            return write!(f, "{}{}e-{} {}", sign, units, self.scale, self.currency);
+        };
+        write!(
+            f,
+            "{}{}.{:0width$} {}",
+            sign,
+            u128::from(units) / divisor,
+            u128::from(units) % divisor,
+            self.currency,
+            width = usize::from(self.scale)
+        )
+    }
+}
+
+/// Serialized forms of money
+///
+/// A bare number is an amount in minor units of `DEFAULT_CURRENCY`,
+/// as written before currencies were supported. JSON readers replace it
+/// with the default currency of their `ReadOptions`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MoneyRepr {
+    MinorUnits(i64),
+    Full {
+        minor_units: i64,
+        currency: CurrencyCode,
+        scale: Option<u8>,
+    },
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match MoneyRepr::deserialize(deserializer)? {
+            MoneyRepr::MinorUnits(minor_units) => Ok(Money::new(minor_units, DEFAULT_CURRENCY)),
+            MoneyRepr::Full { minor_units, currency, scale } => {
+                Money::with_scale(minor_units, currency, scale.unwrap_or(currency.minor_unit()))
+                    .map_err(de::Error::custom)
+            }
+        }
+    }
+}
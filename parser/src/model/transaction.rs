@@ -1,11 +1,13 @@
 use std::fmt;
-use serde::Serialize;
-use crate::errors::ParseError;
+use serde::{Deserialize, Serialize};
+use crate::errors::{ParseError, ParseErrorKind};
+use crate::validation::check_required;
+use crate::model::money::Money;
 use crate::model::transaction_type::TransactionType;
 use crate::model::transaction_status::TransactionStatus;
 
 /// Transaction
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Transaction {
     /// Transaction identifier
     pub tx_id: u64,
@@ -16,7 +18,7 @@ pub struct Transaction {
     /// User id for transfer and deposit
     pub to_user_id: u64,
     /// Transaction amount
-    pub amount: i64,
+    pub amount: Money,
     /// Transaction timestamp in Unix epoch millis
     pub timestamp: u64,
     /// Transaction status
@@ -56,7 +58,8 @@ impl Transaction {
         }
     }
     
-    /// Common transaction requirements validation
+    /// Common transaction requirements validation: positive amount and user ids,
+    /// see `Validator::default()`
     ///
     /// # Returning value
     ///
@@ -64,18 +67,114 @@ impl Transaction {
     /// - Ok(()) - successful transaction validation
     /// - Err(ParseError) - parsing error
     pub fn validate(&self) -> Result<(), ParseError> {
-        if self.tx_type == TransactionType::Deposit && self.from_user_id != 0 {
-            return Err(ParseError::Validation(format!(
-                "FROM_USER_ID must be 0 for DEPOSIT, got {}",
-                self.from_user_id
-            )));
-        }
-        if self.tx_type == TransactionType::Withdrawal && self.to_user_id != 0 {
-            return Err(ParseError::Validation(format!(
-                "TO_USER_ID must be 0 for WITHDRAWAL, got {}",
-                self.to_user_id
-            )));
-        }
-        Ok(())
+        check_required(&self.into())
+    }
+
+    /// Creates a builder, which checks transaction requirements before constructing it
+    ///
+    /// # Returning value
+    ///
+    /// Returns `TransactionBuilder` - builder with no fields set
+    pub fn builder() -> TransactionBuilder {
+        TransactionBuilder::default()
+    }
+}
+
+/// Builder of a valid transaction
+///
+/// `TX_ID`, `TX_TYPE`, `AMOUNT`, `TIMESTAMP` and `STATUS` are required, user ids
+/// default to the system user `0` and description defaults to an empty string.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    tx_id: Option<u64>,
+    tx_type: Option<TransactionType>,
+    from_user_id: u64,
+    to_user_id: u64,
+    amount: Option<Money>,
+    timestamp: Option<u64>,
+    status: Option<TransactionStatus>,
+    description: String,
+}
+
+impl TransactionBuilder {
+    /// Sets transaction identifier
+    pub fn with_tx_id(mut self, tx_id: u64) -> Self {
+        self.tx_id = Some(tx_id);
+        self
+    }
+
+    /// Sets transaction type
+    pub fn with_tx_type(mut self, tx_type: TransactionType) -> Self {
+        self.tx_type = Some(tx_type);
+        self
+    }
+
+    /// Sets user id for transfer and withdrawal
+    pub fn with_from_user_id(mut self, from_user_id: u64) -> Self {
+        self.from_user_id = from_user_id;
+        self
+    }
+
+    /// Sets user id for transfer and deposit
+    pub fn with_to_user_id(mut self, to_user_id: u64) -> Self {
+        self.to_user_id = to_user_id;
+        self
+    }
+
+    /// Sets transaction amount
+    pub fn with_amount(mut self, amount: Money) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Sets transaction timestamp in Unix epoch millis
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets transaction status
+    pub fn with_status(mut self, status: TransactionStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets transaction description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
     }
-}
\ No newline at end of file
+
+    /// Constructs the transaction
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Transaction, ParseError>`:
+    /// - Ok(Transaction) - valid transaction
+    /// - Err(ParseError) - required field is not set, amount is not positive
+    ///   or user ids do not conform to the transaction type
+    pub fn build(self) -> Result<Transaction, ParseError> {
+        let tx_id = required(self.tx_id, "TX_ID")?;
+        let tx_type = required(self.tx_type, "TX_TYPE")?;
+        let amount = required(self.amount, "AMOUNT")?;
+        let timestamp = required(self.timestamp, "TIMESTAMP")?;
+        let status = required(self.status, "STATUS")?;
+        let transaction = Transaction {
+            tx_id,
+            tx_type,
+            from_user_id: self.from_user_id,
+            to_user_id: self.to_user_id,
+            amount,
+            timestamp,
+            status,
+            description: self.description,
+        };
+        transaction.validate()?;
+        Ok(transaction)
+    }
+}
+
+/// Returns value of a required builder field or `MissingField` error
+fn required<T>(value: Option<T>, field_name: &str) -> Result<T, ParseError> {
+    value.ok_or_else(|| ParseError::new(ParseErrorKind::MissingField(field_name.to_string())).with_field(field_name))
+}
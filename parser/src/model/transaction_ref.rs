@@ -0,0 +1,76 @@
+use crate::errors::ParseError;
+use crate::model::money::Money;
+use crate::model::transaction::Transaction;
+use crate::model::transaction_status::TransactionStatus;
+use crate::model::transaction_type::TransactionType;
+use crate::validation::check_required;
+
+/// Borrowed view of a transaction
+///
+/// Has the same fields as `Transaction`, but the description points into
+/// the parsed data instead of being allocated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransactionRef<'a> {
+    /// Transaction identifier
+    pub tx_id: u64,
+    /// Transaction type
+    pub tx_type: TransactionType,
+    /// User id for transfer and withdrawal
+    pub from_user_id: u64,
+    /// User id for transfer and deposit
+    pub to_user_id: u64,
+    /// Transaction amount
+    pub amount: Money,
+    /// Transaction timestamp in Unix epoch millis
+    pub timestamp: u64,
+    /// Transaction status
+    pub status: TransactionStatus,
+    /// Transaction desription
+    pub description: &'a str,
+}
+
+impl TransactionRef<'_> {
+    /// Copies the view into an owned transaction
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Transaction` - transaction with the allocated description
+    pub fn to_transaction(&self) -> Transaction {
+        Transaction {
+            tx_id: self.tx_id,
+            tx_type: self.tx_type,
+            from_user_id: self.from_user_id,
+            to_user_id: self.to_user_id,
+            amount: self.amount,
+            timestamp: self.timestamp,
+            status: self.status,
+            description: self.description.to_string(),
+        }
+    }
+
+    /// Common transaction requirements validation, the same as `Transaction::validate`
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(), ParseError>`:
+    /// - Ok(()) - successful transaction validation
+    /// - Err(ParseError) - parsing error
+    pub fn validate(&self) -> Result<(), ParseError> {
+        check_required(self)
+    }
+}
+
+impl<'a> From<&'a Transaction> for TransactionRef<'a> {
+    fn from(transaction: &'a Transaction) -> Self {
+        Self {
+            tx_id: transaction.tx_id,
+            tx_type: transaction.tx_type,
+            from_user_id: transaction.from_user_id,
+            to_user_id: transaction.to_user_id,
+            amount: transaction.amount,
+            timestamp: transaction.timestamp,
+            status: transaction.status,
+            description: &transaction.description,
+        }
+    }
+}
@@ -1,10 +1,10 @@
-use serde::Serialize;
-use crate::errors::ParseError;
+use serde::{Deserialize, Serialize};
+use crate::errors::{ParseError, ParseErrorKind};
 use clap::ValueEnum;
 use strum_macros::{EnumString, Display};
 
 /// Acceptable transaction statuses
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[derive(Clone, Copy, Eq, ValueEnum)]
 #[derive(EnumString, Display)]
 #[strum(serialize_all = "UPPERCASE")]
@@ -40,7 +40,7 @@ impl TransactionStatus {
             0 => Ok(Self::Success),
             1 => Ok(Self::Failure),
             2 => Ok(Self::Pending),
-            _ => Err(ParseError::InvalidTransactionStatus(format!("Invalid transaction status: {}", value))),
+            _ => Err(ParseErrorKind::InvalidTransactionStatus(value.to_string()).into()),
         }
     }
     
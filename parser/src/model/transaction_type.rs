@@ -1,10 +1,10 @@
-use serde::Serialize;
-use crate::errors::ParseError;
+use serde::{Deserialize, Serialize};
+use crate::errors::{ParseError, ParseErrorKind};
 use clap::ValueEnum;
 use strum_macros::{EnumString, Display};
 
 /// Acceptable transaction types
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[derive(Clone, Copy, Eq, ValueEnum)]
 #[derive(EnumString, Display)]
 #[strum(serialize_all = "UPPERCASE")]
@@ -40,7 +40,7 @@ impl TransactionType {
             0 => Ok(Self::Deposit),
             1 => Ok(Self::Transfer),
             2 => Ok(Self::Withdrawal),
-            _ => Err(ParseError::InvalidTransactionType(format!("Invalid transaction type: {}", value))),
+            _ => Err(ParseErrorKind::InvalidTransactionType(value.to_string()).into()),
         }
     }
     
@@ -1,36 +1,230 @@
-use crate::errors::ParseError;
-use crate::format::Format;
-use crate::format::yp_bank_text_format::YPBankTextParser;
-use crate::format::yp_bank_csv_format::YPBankCsvParser;
-use crate::format::yp_bank_bin_format::YPBankBinParser;
+use crate::compression::decompress;
+use crate::errors::{Location, ParseError, ParseErrorKind, RecordError};
+use crate::format::{Format, ReadOptions, TransactionFormat, TransactionIter, TransactionWriter, WriteOptions};
 use crate::model::transaction::Transaction;
+use crate::validation::Validator;
+use std::sync::Arc;
+
+/// Behaviour on an invalid record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnError {
+    /// Stop parsing and return the first error
+    #[default]
+    Fail,
+    /// Skip invalid records silently
+    Skip,
+    /// Skip invalid records and collect their errors
+    Collect,
+}
+
+/// Parsing options
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Behaviour on an invalid record
+    pub on_error: OnError,
+    /// Additional rules, checked for every transaction, which satisfies the format requirements
+    pub validator: Option<Arc<Validator>>,
+    /// Settings of the format reader
+    pub read: ReadOptions,
+}
+
+/// Result of parsing with options
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    /// Successfully parsed transactions
+    pub transactions: Vec<Transaction>,
+    /// Errors of rejected records, collected in `OnError::Collect` mode
+    pub errors: Vec<RecordError>,
+    /// Violations of warning rules of `ParseOptions::validator`, the transactions are kept
+    pub warnings: Vec<RecordError>,
+}
 
 /// Parser
+///
+/// Works with any `TransactionFormat`: a built-in `Format`, a format
+/// found in the `registry` or a custom implementation.
 pub struct Parser;
 impl Parser {
     /// Parses data from different sources.
     ///
+    /// gzip and zstd compressed data is decompressed transparently, see `iter`.
+    ///
     /// # Parameters
     ///
     /// * `reader` — any type, implementing `std::io::Read`, from which data can be read
+    /// * `format` - format to read data from
     ///
     /// # Returning value
     ///
     /// Returns `Result<Vec<Transaction>, ParseError>`:
     /// - `Ok(Vec<Transaction>)` — successful parsing, contains transaction vector
     /// - `Err(ParseError)` — parsing error (I/O, CSV, validation etc.)
-    pub fn parse<R: std::io::Read>(mut reader: R, format: Format) -> Result<Vec<Transaction>, ParseError> {
-        let transactions = match format {
-            Format::Text => YPBankTextParser::parse(reader)?,
-            Format::Csv => YPBankCsvParser::parse(reader)?,
-            Format::Binary => YPBankBinParser::parse(&mut reader)?
+    pub fn parse<R: std::io::Read, F: TransactionFormat>(reader: R, format: F) -> Result<Vec<Transaction>, ParseError> {
+        Self::iter(reader, format).collect()
+    }
+
+    /// Parses data from different sources with the chosen error handling.
+    ///
+    /// In `OnError::Skip` and `OnError::Collect` modes invalid records are skipped
+    /// and parsing continues with the next one. Errors, which break the whole
+    /// input (I/O, CSV headers, binary magic), are always returned as `Err`.
+    /// Violations of error rules of `ParseOptions::validator` reject the record
+    /// the same way, violations of warning rules are collected into `ParseReport::warnings`.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which data can be read
+    /// * `format` - format to read data from
+    /// * `options` - parsing options
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<ParseReport, ParseError>`:
+    /// - `Ok(ParseReport)` — parsed transactions and errors of rejected records
+    /// - `Err(ParseError)` — parsing error
+    pub fn parse_with_options<R: std::io::Read, F: TransactionFormat>(
+        reader: R,
+        format: F,
+        options: &ParseOptions,
+    ) -> Result<ParseReport, ParseError> {
+        let mut report = ParseReport::default();
+        let mut next_record_index = 0;
+        for result in Self::iter_with_options(reader, format, &options.read) {
+            // bytes, skipped while resynchronizing, are reported before the next record
+            let is_record = !matches!(&result, Err(error) if matches!(error.kind(), ParseErrorKind::SkippedBytes(..)));
+            let record_index = next_record_index;
+            next_record_index += usize::from(is_record);
+            let location = Location {
+                record_index: Some(record_index),
+                ..Location::default()
+            };
+            let result = match (result, &options.validator) {
+                (Ok(transaction), Some(validator)) => match validator.validate(&transaction) {
+                    Ok(warnings) => {
+                        report.warnings.extend(warnings.into_iter().map(|warning| RecordError {
+                            record_index,
+                            error: warning.error.with_location(location.clone()),
+                        }));
+                        Ok(transaction)
+                    }
+                    Err(error) => Err(error.with_location(location)),
+                },
+                (result, _) => result,
+            };
+            match result {
+                Ok(transaction) => report.transactions.push(transaction),
+                Err(error) if options.on_error == OnError::Fail || !error.is_recoverable() => {
+                    return Err(error);
+                }
+                Err(_) if options.on_error == OnError::Skip => {}
+                Err(error) => report.errors.push(RecordError { record_index, error }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Parses data from different sources using all threads of the `rayon` pool.
+    ///
+    /// The whole input is read into memory and split into chunks of whole records
+    /// with `TransactionFormat::split`, chunks are parsed concurrently. Transactions
+    /// are returned in the input order, the first error in the input order is returned
+    /// with its location in the whole input. Formats, which can not be split,
+    /// are parsed sequentially.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which data can be read
+    /// * `format` - format to read data from
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Vec<Transaction>, ParseError>`:
+    /// - `Ok(Vec<Transaction>)` — successful parsing, contains transaction vector
+    /// - `Err(ParseError)` — parsing error (I/O, CSV, validation etc.)
+    #[cfg(feature = "rayon")]
+    pub fn parse_parallel<R: std::io::Read, F: TransactionFormat>(
+        reader: R,
+        format: F,
+    ) -> Result<Vec<Transaction>, ParseError> {
+        use rayon::prelude::*;
+        use std::io::Read;
+
+        let mut data = Vec::new();
+        decompress(reader)?.read_to_end(&mut data)?;
+        let Some(chunks) = format.split(&data, rayon::current_num_threads()) else {
+            return Self::parse(data.as_slice(), format);
         };
+        let results: Vec<Vec<Result<Transaction, ParseError>>> = chunks
+            .par_iter()
+            .map(|chunk| {
+                let mut results = Vec::new();
+                for result in format.reader(Box::new(chunk.prefix.chain(chunk.data))) {
+                    let failed = result.is_err();
+                    results.push(result);
+                    if failed {
+                        break;
+                    }
+                }
+                results
+            })
+            .collect();
+        let mut transactions = Vec::new();
+        for (chunk, results) in chunks.iter().zip(results) {
+            let records_before = transactions.len();
+            for result in results {
+                transactions.push(result.map_err(|error| chunk.locate(error, records_before))?);
+            }
+        }
         Ok(transactions)
     }
 
+    /// Creates a streaming iterator over transactions from different sources.
+    ///
+    /// Unlike `parse`, records are read and parsed lazily, so inputs of any size
+    /// can be processed in constant memory. gzip and zstd compressed data is detected
+    /// by its "magic" bytes and decompressed on the fly.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which data can be read
+    /// * `format` - format to read data from
+    ///
+    /// # Returning value
+    ///
+    /// Returns `TransactionIter` - iterator, which yields parsed transactions
+    /// and errors of invalid records
+    pub fn iter<'a, R: std::io::Read + 'a, F: TransactionFormat>(reader: R, format: F) -> TransactionIter<'a> {
+        Self::iter_with_options(reader, format, &ReadOptions::default())
+    }
+
+    /// Creates a streaming iterator over transactions with the chosen reader settings.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::Read`, from which data can be read
+    /// * `format` - format to read data from
+    /// * `options` - settings of the format reader, such as the default currency
+    ///
+    /// # Returning value
+    ///
+    /// Returns `TransactionIter` - iterator, which yields parsed transactions
+    /// and errors of invalid records
+    pub fn iter_with_options<'a, R: std::io::Read + 'a, F: TransactionFormat>(
+        reader: R,
+        format: F,
+        options: &ReadOptions,
+    ) -> TransactionIter<'a> {
+        match decompress(reader) {
+            Ok(reader) => format.reader_with_options(reader, options),
+            Err(error) => Box::new(std::iter::once(Err(error))),
+        }
+    }
+
     /// Writes transaction vector into chosen sink.
     ///
     /// Function serializes every transaction into chosen format and writes it into `writer`.
+    /// All transactions are known in advance, so currencies are written, if any of them needs it,
+    /// see `WriteOptions::for_transactions`.
     ///
     /// # Parameters
     ///
@@ -43,12 +237,69 @@ impl Parser {
     /// Returns `Result<(), ParseError>`:
     /// - `Ok(())` — all transactions successfully written
     /// - `Err(ParseError)` — write or validation error 
-    pub fn write<W: std::io::Write>(transactions: &[Transaction], mut writer: W, format: Format,) -> Result<(), ParseError> {
-        match format {
-            Format::Text => YPBankTextParser::write(transactions, &mut writer, true),
-            Format::Csv => YPBankCsvParser::write(transactions, &mut writer),
-            Format::Binary => YPBankBinParser::write(transactions, &mut writer)
+    pub fn write<W: std::io::Write, F: TransactionFormat>(transactions: &[Transaction], writer: W, format: F,) -> Result<(), ParseError> {
+        let options = WriteOptions::for_transactions(transactions);
+        let mut transaction_writer = Self::writer_with_options(writer, format, &options);
+        for transaction in transactions {
+            transaction_writer.write_one(transaction)?;
         }
+        transaction_writer.finish()
+    }
+
+    /// Creates an incremental writer of transactions into chosen sink.
+    ///
+    /// Together with `iter` allows converting data between formats in constant memory.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which data will be written
+    /// * `format` - format to write data into
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Box<dyn TransactionWriter>` - writer, serializing transactions one at a time
+    pub fn writer<'a, W: std::io::Write + 'a, F: TransactionFormat>(writer: W, format: F) -> Box<dyn TransactionWriter + 'a> {
+        format.writer(Box::new(writer))
+    }
+
+    /// Creates an incremental writer of transactions into chosen sink with the writing settings.
+    ///
+    /// # Parameters
+    ///
+    /// * `writer` — any type, implementing `std::io::Write`, into which data will be written
+    /// * `format` - format to write data into
+    /// * `options` - writing settings, such as writing currencies of all amounts
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Box<dyn TransactionWriter>` - writer, serializing transactions one at a time
+    pub fn writer_with_options<'a, W: std::io::Write + 'a, F: TransactionFormat>(
+        writer: W,
+        format: F,
+        options: &WriteOptions,
+    ) -> Box<dyn TransactionWriter + 'a> {
+        format.writer_with_options(Box::new(writer), options)
+    }
+
+    /// Detects the format of buffered data without consuming it.
+    ///
+    /// Recognizes YPBankBin by `MAGIC` bytes, JSON by `[`, JSON Lines by `{`,
+    /// YPBankCsv by its header and YPBankText by `KEY: value` lines. Only the bytes already available
+    /// in the reader buffer are inspected.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader` — any type, implementing `std::io::BufRead`, from which data can be read
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Option<Format>`:
+    /// - `Some(Format)` — detected format
+    /// - `None` — data does not look like any built-in format or can not be read
+    pub fn detect_format<R: std::io::BufRead>(reader: &mut R) -> Option<Format> {
+        let head = reader.fill_buf().ok()?;
+        [Format::Binary, Format::Json, Format::JsonLines, Format::Csv, Format::Text]
+            .into_iter()
+            .find(|format| format.detect(head))
     }
-    
-}
\ No newline at end of file
+}
@@ -0,0 +1,233 @@
+use crate::model::transaction::Transaction;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+/// Transaction field, compared during reconciliation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+#[derive(EnumString, Display, IntoStaticStr)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[value(rename_all = "snake_case")]
+pub enum TransactionField {
+    /// Transaction type
+    TxType,
+    /// Sender user id
+    FromUserId,
+    /// Recipient user id
+    ToUserId,
+    /// Amount
+    Amount,
+    /// Timestamp
+    Timestamp,
+    /// Status
+    Status,
+    /// Description
+    Description,
+}
+
+/// Rules of transaction comparison
+///
+/// The default policy compares all fields exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComparePolicy {
+    /// Fields, which are not compared
+    pub ignored_fields: Vec<TransactionField>,
+    /// Maximal difference of timestamps in milliseconds, which is still considered equal
+    pub timestamp_tolerance: u64,
+    /// Compare descriptions ignoring letter case
+    pub ignore_description_case: bool,
+    /// Compare descriptions ignoring leading, trailing and repeated whitespace
+    pub ignore_description_whitespace: bool,
+}
+
+impl ComparePolicy {
+    /// Compares fields of two transactions according to the policy
+    ///
+    /// # Parameters
+    ///
+    /// * `left` — transaction of the left input
+    /// * `right` — transaction of the right input
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Vec<FieldDiff>` - differing fields, empty if the transactions are considered equal
+    pub fn diff(&self, left: &Transaction, right: &Transaction) -> Vec<FieldDiff> {
+        let mut fields = Vec::new();
+        let mut compare = |field: TransactionField, left: String, right: String, equal: bool| {
+            if !equal && !self.ignored_fields.contains(&field) {
+                fields.push(FieldDiff { field: field.name(), left, right });
+            }
+        };
+        compare(
+            TransactionField::TxType,
+            left.tx_type.to_string(),
+            right.tx_type.to_string(),
+            left.tx_type == right.tx_type,
+        );
+        compare(
+            TransactionField::FromUserId,
+            left.from_user_id.to_string(),
+            right.from_user_id.to_string(),
+            left.from_user_id == right.from_user_id,
+        );
+        compare(
+            TransactionField::ToUserId,
+            left.to_user_id.to_string(),
+            right.to_user_id.to_string(),
+            left.to_user_id == right.to_user_id,
+        );
+        compare(
+            TransactionField::Amount,
+            left.amount.to_string(),
+            right.amount.to_string(),
+            left.amount == right.amount,
+        );
+        compare(
+            TransactionField::Timestamp,
+            left.timestamp.to_string(),
+            right.timestamp.to_string(),
+            left.timestamp.abs_diff(right.timestamp) <= self.timestamp_tolerance,
+        );
+        compare(
+            TransactionField::Status,
+            left.status.to_string(),
+            right.status.to_string(),
+            left.status == right.status,
+        );
+        compare(
+            TransactionField::Description,
+            left.description.clone(),
+            right.description.clone(),
+            self.normalize_description(&left.description) == self.normalize_description(&right.description),
+        );
+        fields
+    }
+
+    /// Brings the description to the form, in which it is compared
+    fn normalize_description(&self, description: &str) -> String {
+        let description = if self.ignore_description_whitespace {
+            description.split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            description.to_string()
+        };
+        if self.ignore_description_case {
+            description.to_lowercase()
+        } else {
+            description
+        }
+    }
+}
+
+impl TransactionField {
+    /// # Returning value
+    ///
+    /// Returns `&'static str` - field name, as used in the formats, e.g. `TX_TYPE`
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+}
+
+/// Difference of a single field of two transactions with the same ID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldDiff {
+    /// Field name, e.g. `AMOUNT`
+    pub field: &'static str,
+    /// Field value in the left input
+    pub left: String,
+    /// Field value in the right input
+    pub right: String,
+}
+
+/// Transaction, which is present in both inputs with different fields
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordDiff {
+    /// Transaction in the left input
+    pub left: Transaction,
+    /// Transaction in the right input
+    pub right: Transaction,
+    /// Differing fields
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Result of reconciliation of two transaction lists by transaction ID
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Reconciliation {
+    /// Transactions, which are present only in the left input, in the left input order
+    pub left_only: Vec<Transaction>,
+    /// Transactions, which are present only in the right input, in the right input order
+    pub right_only: Vec<Transaction>,
+    /// Transactions, which are present in both inputs with different fields, in the left input order
+    pub different: Vec<RecordDiff>,
+    /// Number of transactions, which are equal in both inputs
+    pub matched: usize,
+}
+
+impl Reconciliation {
+    /// # Returning value
+    ///
+    /// Returns `bool` - `true` if both inputs contain the same transactions
+    pub fn is_identical(&self) -> bool {
+        self.left_only.is_empty() && self.right_only.is_empty() && self.different.is_empty()
+    }
+}
+
+/// Matches transactions of two lists by `tx_id` regardless of their order, comparing all fields exactly
+///
+/// # Parameters
+///
+/// * `left` — transactions of the first input
+/// * `right` — transactions of the second input
+///
+/// # Returning value
+///
+/// Returns `Reconciliation` - transactions, found only in one of the inputs, and differing transactions
+pub fn reconcile(left: &[Transaction], right: &[Transaction]) -> Reconciliation {
+    reconcile_with_policy(left, right, &ComparePolicy::default())
+}
+
+/// Matches transactions of two lists by `tx_id` regardless of their order
+///
+/// Transactions with a repeated ID are matched in the order of their appearance.
+///
+/// # Parameters
+///
+/// * `left` — transactions of the first input
+/// * `right` — transactions of the second input
+/// * `policy` — rules of field comparison
+///
+/// # Returning value
+///
+/// Returns `Reconciliation` - transactions, found only in one of the inputs, and differing transactions
+pub fn reconcile_with_policy(left: &[Transaction], right: &[Transaction], policy: &ComparePolicy) -> Reconciliation {
+    let mut right_by_id: HashMap<u64, VecDeque<usize>> = HashMap::new();
+    for (index, transaction) in right.iter().enumerate() {
+        right_by_id.entry(transaction.tx_id).or_default().push_back(index);
+    }
+    let mut right_matched = vec![false; right.len()];
+    let mut reconciliation = Reconciliation::default();
+    for transaction in left {
+        let Some(index) = right_by_id.get_mut(&transaction.tx_id).and_then(VecDeque::pop_front) else {
+            reconciliation.left_only.push(transaction.clone());
+            continue;
+        };
+        right_matched[index] = true;
+        let fields = policy.diff(transaction, &right[index]);
+        if fields.is_empty() {
+            reconciliation.matched += 1;
+        } else {
+            reconciliation.different.push(RecordDiff {
+                left: transaction.clone(),
+                right: right[index].clone(),
+                fields,
+            });
+        }
+    }
+    reconciliation.right_only = right
+        .iter()
+        .zip(right_matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(transaction, _)| transaction.clone())
+        .collect();
+    reconciliation
+}
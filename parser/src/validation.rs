@@ -0,0 +1,321 @@
+use crate::errors::{ParseError, ParseErrorKind};
+use crate::model::transaction::Transaction;
+use crate::model::transaction_ref::TransactionRef;
+use crate::model::transaction_type::TransactionType;
+use std::fmt;
+use strum_macros::Display;
+
+/// Severity of a rule violation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// Transaction is rejected
+    #[default]
+    Error,
+    /// Transaction is accepted, the violation is only reported
+    Warning,
+}
+
+/// Requirement to a transaction
+///
+/// Implemented by the built-in rules of this module, custom rules can implement it
+/// directly or be created from a closure with `FnRule`.
+pub trait Rule: Send + Sync {
+    /// Rule name, used in reports
+    fn name(&self) -> &str;
+
+    /// Checks the transaction
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction` — transaction to check
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<(), ParseError>`:
+    /// - `Ok(())` — transaction satisfies the rule
+    /// - `Err(ParseError)` — violation with the field it concerns
+    fn check(&self, transaction: &TransactionRef<'_>) -> Result<(), ParseError>;
+}
+
+/// Amount must be greater than zero
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PositiveAmount;
+
+impl Rule for PositiveAmount {
+    fn name(&self) -> &str {
+        "positive-amount"
+    }
+
+    fn check(&self, transaction: &TransactionRef<'_>) -> Result<(), ParseError> {
+        if transaction.amount.minor_units <= 0 {
+            return Err(ParseError::new(ParseErrorKind::InvalidAmount(transaction.amount.minor_units))
+                .with_field("AMOUNT"));
+        }
+        Ok(())
+    }
+}
+
+/// Deposits must come from and withdrawals must go to the system user `0`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UserIds;
+
+impl Rule for UserIds {
+    fn name(&self) -> &str {
+        "user-ids"
+    }
+
+    fn check(&self, transaction: &TransactionRef<'_>) -> Result<(), ParseError> {
+        let tx_type = transaction.tx_type;
+        if tx_type == TransactionType::Deposit && transaction.from_user_id != 0 {
+            return Err(ParseError::new(ParseErrorKind::InvalidUserId(
+                "FROM_USER_ID",
+                transaction.from_user_id,
+                tx_type,
+            )).with_field("FROM_USER_ID"));
+        }
+        if tx_type == TransactionType::Withdrawal && transaction.to_user_id != 0 {
+            return Err(ParseError::new(ParseErrorKind::InvalidUserId(
+                "TO_USER_ID",
+                transaction.to_user_id,
+                tx_type,
+            )).with_field("TO_USER_ID"));
+        }
+        Ok(())
+    }
+}
+
+/// Transfer must go to another user
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NonSelfTransfer;
+
+impl Rule for NonSelfTransfer {
+    fn name(&self) -> &str {
+        "non-self-transfer"
+    }
+
+    fn check(&self, transaction: &TransactionRef<'_>) -> Result<(), ParseError> {
+        if transaction.tx_type == TransactionType::Transfer && transaction.from_user_id == transaction.to_user_id {
+            return Err(ParseError::new(ParseErrorKind::Validation(format!(
+                "transfer from user {} to itself",
+                transaction.from_user_id
+            )))
+            .with_field("TO_USER_ID"));
+        }
+        Ok(())
+    }
+}
+
+/// Timestamp must be within the range, bounds are Unix epoch millis and inclusive
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampRange {
+    /// The earliest allowed timestamp
+    pub min: u64,
+    /// The latest allowed timestamp
+    pub max: u64,
+}
+
+impl Rule for TimestampRange {
+    fn name(&self) -> &str {
+        "timestamp-range"
+    }
+
+    fn check(&self, transaction: &TransactionRef<'_>) -> Result<(), ParseError> {
+        if !(self.min..=self.max).contains(&transaction.timestamp) {
+            return Err(ParseError::new(ParseErrorKind::Validation(format!(
+                "timestamp {} is out of range {}..={}",
+                transaction.timestamp, self.min, self.max
+            )))
+            .with_field("TIMESTAMP"));
+        }
+        Ok(())
+    }
+}
+
+/// Description must fit the length and consist of the allowed characters
+#[derive(Debug, Clone, Copy)]
+pub struct DescriptionRule {
+    /// Maximum length in characters
+    pub max_length: usize,
+    /// Predicate of allowed characters
+    pub allowed: fn(char) -> bool,
+}
+
+impl Default for DescriptionRule {
+    /// Up to 255 characters without control characters
+    fn default() -> Self {
+        Self {
+            max_length: 255,
+            allowed: |c| !c.is_control(),
+        }
+    }
+}
+
+impl Rule for DescriptionRule {
+    fn name(&self) -> &str {
+        "description"
+    }
+
+    fn check(&self, transaction: &TransactionRef<'_>) -> Result<(), ParseError> {
+        let error = |message: String| ParseError::new(ParseErrorKind::Validation(message)).with_field("DESCRIPTION");
+        let length = transaction.description.chars().count();
+        if length > self.max_length {
+            return Err(error(format!(
+                "description is {} characters long, at most {} allowed",
+                length, self.max_length
+            )));
+        }
+        if let Some(c) = transaction.description.chars().find(|&c| !(self.allowed)(c)) {
+            return Err(error(format!("description contains not allowed character {:?}", c)));
+        }
+        Ok(())
+    }
+}
+
+/// Custom rule, created from a closure
+pub struct FnRule<F> {
+    name: String,
+    check: F,
+}
+
+impl<F> FnRule<F>
+where
+    F: Fn(&TransactionRef<'_>) -> Result<(), ParseError> + Send + Sync,
+{
+    /// Creates a new rule
+    ///
+    /// # Parameters
+    ///
+    /// * `name` — rule name, used in reports
+    /// * `check` — closure, returning a violation as `Err`
+    pub fn new(name: impl Into<String>, check: F) -> Self {
+        Self { name: name.into(), check }
+    }
+}
+
+impl<F> Rule for FnRule<F>
+where
+    F: Fn(&TransactionRef<'_>) -> Result<(), ParseError> + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self, transaction: &TransactionRef<'_>) -> Result<(), ParseError> {
+        (self.check)(transaction)
+    }
+}
+
+/// Rule violation found by `Validator`
+#[derive(Debug)]
+pub struct Violation {
+    /// Name of the violated rule
+    pub rule: String,
+    /// Severity, the rule was registered with
+    pub severity: Severity,
+    /// Description of the violation
+    pub error: ParseError,
+}
+
+/// Set of rules with their severities
+///
+/// `Validator::new()` has no rules, `Validator::default()` has the rules
+/// every format enforces: `PositiveAmount` and `UserIds` as errors.
+pub struct Validator {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+impl Validator {
+    /// Creates a validator without rules
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Registers a rule
+    ///
+    /// # Parameters
+    ///
+    /// * `rule` — rule to register
+    /// * `severity` — whether a violation rejects the transaction or is only reported
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Validator` - the validator with the rule
+    pub fn with_rule(mut self, rule: impl Rule + 'static, severity: Severity) -> Self {
+        self.rules.push((Box::new(rule), severity));
+        self
+    }
+
+    /// Checks the transaction against all rules
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction` — transaction to check
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Vec<Violation>` - violations in the order of rules registration
+    pub fn check<'a>(&self, transaction: impl Into<TransactionRef<'a>>) -> Vec<Violation> {
+        let transaction = transaction.into();
+        self.rules
+            .iter()
+            .filter_map(|(rule, severity)| {
+                rule.check(&transaction).err().map(|error| Violation {
+                    rule: rule.name().to_string(),
+                    severity: *severity,
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    /// Validates the transaction
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction` — transaction to validate
+    ///
+    /// # Returning value
+    ///
+    /// Returns `Result<Vec<Violation>, ParseError>`:
+    /// - `Ok(Vec<Violation>)` — transaction is accepted, contains violations of warning rules
+    /// - `Err(ParseError)` — violation of the first error rule
+    pub fn validate<'a>(&self, transaction: impl Into<TransactionRef<'a>>) -> Result<Vec<Violation>, ParseError> {
+        let mut warnings = Vec::new();
+        for violation in self.check(transaction) {
+            match violation.severity {
+                Severity::Error => return Err(violation.error),
+                Severity::Warning => warnings.push(violation),
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new()
+            .with_rule(PositiveAmount, Severity::Error)
+            .with_rule(UserIds, Severity::Error)
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.rules.iter().map(|(rule, severity)| (rule.name(), severity)))
+            .finish()
+    }
+}
+
+/// Checks the rules every format enforces, the same as `Validator::default()`,
+/// without allocating the validator
+pub(crate) fn check_required(transaction: &TransactionRef<'_>) -> Result<(), ParseError> {
+    PositiveAmount.check(transaction)?;
+    UserIds.check(transaction)
+}
+
+/// Checks only the amount before writing, other requirements are left to the reader
+pub(crate) fn check_amount(transaction: &Transaction) -> Result<(), ParseError> {
+    PositiveAmount.check(&transaction.into())
+}
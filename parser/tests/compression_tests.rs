@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::compression::Compression;
+    use financial_parser::format::Format;
+    use financial_parser::parser::Parser;
+    #[cfg(feature = "compression")]
+    use financial_parser::{
+        model::currency::CurrencyCode, model::money::Money, model::transaction::Transaction,
+        model::transaction_status::TransactionStatus, model::transaction_type::TransactionType,
+    };
+
+    #[cfg(feature = "compression")]
+    fn transactions() -> Vec<Transaction> {
+        (1..=3)
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 501,
+                amount: Money::new(100 * tx_id as i64, CurrencyCode::USD),
+                timestamp: 1672531200000,
+                status: TransactionStatus::Success,
+                description: format!("Deposit {}", tx_id),
+            })
+            .collect()
+    }
+
+    /// Checks if compression is detected by "magic" bytes
+    #[test]
+    fn test_detect_compression() {
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08, 0x00]), Some(Compression::Gzip));
+        assert_eq!(Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]), Some(Compression::Zstd));
+        assert_eq!(Compression::detect(b"YPBN"), None);
+        assert_eq!(Compression::detect(&[0x1f]), None);
+    }
+
+    /// Checks if compressed data of every format is decompressed transparently
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_compressed_round_trip() {
+        use financial_parser::compression::compress;
+        use financial_parser::format::registry::find_input_format;
+
+        let transactions = transactions();
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            for format in [Format::Text, Format::Csv, Format::Binary, Format::Json, Format::JsonLines] {
+                let mut writer = compress(Vec::new(), compression).unwrap();
+                Parser::write(&transactions, &mut writer, format).unwrap();
+                let output = writer.finish().unwrap();
+                assert_eq!(Compression::detect(&output), Some(compression));
+                assert_eq!(Parser::parse(output.as_slice(), format).unwrap(), transactions);
+                let auto = find_input_format("auto").unwrap();
+                assert_eq!(Parser::parse(output.as_slice(), &auto).unwrap(), transactions);
+            }
+        }
+    }
+
+    /// Reader, which fails once after the first chunk
+    struct FailingReader {
+        chunks: Vec<std::io::Result<Vec<u8>>>,
+    }
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.chunks.is_empty() {
+                return Ok(0);
+            }
+            let chunk = self.chunks.remove(0)?;
+            buf[..chunk.len()].copy_from_slice(&chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    /// Checks if an error of the inner reader is not lost after the head bytes
+    #[test]
+    fn test_decompress_keeps_reader_error() {
+        use financial_parser::compression::decompress;
+        use std::io::{Error, ErrorKind, Read};
+
+        let chunks = vec![Ok(b"TX_I".to_vec()), Err(Error::other("disk failure")), Ok(b"D,".to_vec())];
+        let mut reader = decompress(FailingReader { chunks }).unwrap();
+        let mut buf = [0u8; 16];
+        assert_eq!(reader.read(&mut buf).unwrap(), 4);
+        assert_eq!(&buf[..4], b"TX_I");
+        let error = reader.read(&mut buf).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert_eq!(error.to_string(), "disk failure");
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    /// Checks if concatenated gzip members are read as a single stream
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_concatenated_gzip() {
+        use financial_parser::compression::compress;
+
+        let transactions = transactions();
+        let mut data = Vec::new();
+        for transaction in &transactions {
+            let mut writer = compress(Vec::new(), Compression::Gzip).unwrap();
+            Parser::write(std::slice::from_ref(transaction), &mut writer, Format::JsonLines).unwrap();
+            data.extend(writer.finish().unwrap());
+        }
+        assert_eq!(Parser::parse(data.as_slice(), Format::JsonLines).unwrap(), transactions);
+    }
+
+    /// Checks if compressed input is rejected without the `compression` feature
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn test_compression_disabled() {
+        use financial_parser::errors::ParseErrorKind;
+
+        let error = Parser::parse([0x1f, 0x8b, 0x08, 0x00].as_slice(), Format::Csv).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::UnsupportedCompression(Compression::Gzip)));
+    }
+}
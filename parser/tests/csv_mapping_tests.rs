@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::ParseErrorKind;
+    use financial_parser::format::csv_mapping::CsvMapping;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::parser::Parser;
+    use indoc::indoc;
+    use std::io::Cursor;
+
+    const DEBIT_CREDIT_MAPPING: &str = indoc! {r#"
+        delimiter = ";"
+        date_formats = ["%d.%m.%Y %H:%M", "%d.%m.%Y"]
+        decimal_separator = ","
+        thousands_separator = " "
+        currency = "EUR"
+        user_id = 501
+        first_tx_id = 1000
+
+        [columns]
+        date = "Date"
+        debit = "Debit"
+        credit = "Credit"
+        description = "Payee"
+    "#};
+
+    /// Checks if debit and credit columns are mapped to withdrawals and deposits
+    #[test]
+    fn test_debit_credit_mapping() {
+        let data = indoc! {r#"
+            Date;Payee;Debit;Credit
+            01.01.2023;Salary;;1 500,50
+            02.01.2023 10:30;"Coffee; shop";3,2;
+        "#};
+        let mapping = CsvMapping::from_toml(DEBIT_CREDIT_MAPPING).unwrap();
+        let transactions = Parser::parse(Cursor::new(data), &mapping).unwrap();
+        assert_eq!(transactions, vec![
+            Transaction {
+                tx_id: 1000,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 501,
+                amount: Money::new(150050, CurrencyCode::EUR),
+                timestamp: 1672531200000,
+                status: TransactionStatus::Success,
+                description: "Salary".to_string(),
+            },
+            Transaction {
+                tx_id: 1001,
+                tx_type: TransactionType::Withdrawal,
+                from_user_id: 501,
+                to_user_id: 0,
+                amount: Money::new(320, CurrencyCode::EUR),
+                timestamp: 1672655400000,
+                status: TransactionStatus::Success,
+                description: "Coffee; shop".to_string(),
+            },
+        ]);
+    }
+
+    /// Checks if a signed amount column is mapped with a YAML config and errors point at the column
+    #[test]
+    fn test_signed_amount_yaml_mapping() {
+        let mapping = CsvMapping::from_yaml(indoc! {r#"
+            user_id: 7
+            columns:
+              tx_id: Reference
+              date: Booked
+              amount: Amount
+              currency: Currency
+              status: State
+        "#}).unwrap();
+        let data = indoc! {r#"
+            Reference,Booked,Amount,Currency,State
+            42,2023-01-01,-12.5,USD,PENDING
+            43,01/01/2023,10,USD,SUCCESS
+        "#};
+        let results: Vec<_> = Parser::iter(Cursor::new(data), &mapping).collect();
+        let transaction = results[0].as_ref().unwrap();
+        assert_eq!(transaction.tx_id, 42);
+        assert_eq!(transaction.tx_type, TransactionType::Withdrawal);
+        assert_eq!(transaction.from_user_id, 7);
+        assert_eq!(transaction.amount, Money::new(1250, CurrencyCode::USD));
+        assert_eq!(transaction.status, TransactionStatus::Pending);
+        let error = results[1].as_ref().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidDate(value, _) if value == "01/01/2023"));
+        assert_eq!(error.location().field.as_deref(), Some("Booked"));
+        assert_eq!(error.location().line, Some(3));
+    }
+
+    /// Checks if inconsistent mapping configs and missing columns are rejected
+    #[test]
+    fn test_invalid_mapping() {
+        let error = CsvMapping::from_toml("user_id = 1\n[columns]\ndate = \"Date\"\n").unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidMapping(_)));
+        let error = CsvMapping::from_toml("user_id = 1\nunknown = 2\n[columns]\ndate = \"Date\"\namount = \"Sum\"\n").unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidMapping(_)));
+        let mapping = CsvMapping::from_toml(DEBIT_CREDIT_MAPPING).unwrap();
+        let error = Parser::parse(Cursor::new("Date;Payee;Amount\n"), &mapping).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidHeader(..)));
+    }
+}
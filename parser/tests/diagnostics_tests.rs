@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::diagnostics::{render, render_message, render_with_dialect};
+    use financial_parser::errors::{Location, ParseError, ParseErrorKind};
+    use financial_parser::format::yp_bank_csv_format::CsvDialect;
+    use financial_parser::format::yp_bank_bin_format::YPBankBinParser;
+    use financial_parser::format::Format;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::parser::Parser;
+    use indoc::indoc;
+    use std::io::Cursor;
+
+    /// Checks if text format error is rendered with a caret under the field value
+    #[test]
+    fn test_render_text_error() {
+        let data = indoc! {r#"
+            TX_ID: 123
+            TX_TYPE: DEPOSIT
+            FROM_USER_ID: 0
+            TO_USER_ID: 456
+            AMOUNT: 1O0
+            TIMESTAMP: 1633036800000
+            STATUS: SUCCESS
+            DESCRIPTION: "Test"
+        "#};
+        let error = Parser::parse(Cursor::new(data), Format::Text).unwrap_err();
+        let report = render(&error, "input.txt", data.as_bytes());
+        assert!(report.starts_with("error: Invalid number '1O0'"));
+        assert!(report.contains(" --> input.txt: record #1, line 5, column 9, field AMOUNT\n"));
+        assert!(report.contains("5 | AMOUNT: 1O0\n  |         ^^^\n"));
+        assert_eq!(
+            render_message(&error, "<stdin>"),
+            "error: Invalid number '1O0': invalid digit found in string\n --> <stdin>: record #1, line 5, column 9, field AMOUNT\n"
+        );
+    }
+
+    /// Checks if CSV format error is rendered with a caret under the field, found by the header
+    #[test]
+    fn test_render_csv_error() {
+        let data = indoc! {r#"
+            TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+            1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding"
+            1002,TRANSFER,501,502,15000,1672534800000,DONE,"Unknown status"
+        "#};
+        let error = Parser::parse(Cursor::new(data), Format::Csv).unwrap_err();
+        let report = render(&error, "input.csv", data.as_bytes());
+        assert!(report.starts_with("error: Invalid transaction status: DONE"));
+        assert!(report.contains("3 | 1002,TRANSFER,501,502,15000,1672534800000,DONE,\"Unknown status\"\n"));
+        let caret_line = report.lines().last().unwrap();
+        assert_eq!(caret_line, format!("  | {}^^^^", " ".repeat(42)));
+    }
+
+    /// Checks if CSV error is rendered by the delimiter, quote and columns of the dialect
+    #[test]
+    fn test_render_csv_dialect_error() {
+        let data = indoc! {r#"
+            1001;DEPOSIT;0;501;50000;1672531200000;SUCCESS;'Initial; funding'
+            1002;TRANSFER;501;502;15000;1672534800000;DONE;'Unknown status'
+        "#};
+        let dialect = CsvDialect {
+            delimiter: b';',
+            quote: b'\'',
+            columns: Some(CsvDialect::default_columns()[..8].to_vec()),
+            ..CsvDialect::default()
+        };
+        let error = Parser::parse(Cursor::new(data), &dialect).unwrap_err();
+        let report = render_with_dialect(&error, "input.csv", data.as_bytes(), &dialect);
+        assert!(report.contains("2 | 1002;TRANSFER;501;502;15000;1672534800000;DONE;'Unknown status'\n"));
+        assert_eq!(report.lines().last().unwrap(), format!("  | {}^^^^", " ".repeat(42)));
+
+        let data = indoc! {r#"
+            Id;Type;From;To;Sum;Time;State;Note
+            1001;DEPOSIT;0;501;5O000;1672531200000;SUCCESS;'Initial; funding'
+        "#};
+        let dialect = CsvDialect {
+            header_aliases: [("Id", "TX_ID"), ("Type", "TX_TYPE"), ("From", "FROM_USER_ID"), ("To", "TO_USER_ID"),
+                ("Sum", "AMOUNT"), ("Time", "TIMESTAMP"), ("State", "STATUS"), ("Note", "DESCRIPTION")]
+                .into_iter()
+                .map(|(alias, field)| (alias.to_string(), field.to_string()))
+                .collect(),
+            columns: None,
+            ..dialect
+        };
+        let error = Parser::parse(Cursor::new(data), &dialect).unwrap_err();
+        let report = render_with_dialect(&error, "input.csv", data.as_bytes(), &dialect);
+        assert_eq!(report.lines().last().unwrap(), format!("  | {}^^^^^", " ".repeat(19)));
+        // the default dialect can not find the field
+        assert!(!render(&error, "input.csv", data.as_bytes()).contains('^'));
+    }
+
+    /// Checks if binary format error is rendered as a hex dump with a marker under the failing byte
+    #[test]
+    fn test_render_binary_error() {
+        let transaction = Transaction {
+            tx_id: 1,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 456,
+            amount: Money::new(100, CurrencyCode::USD),
+            timestamp: 1633036800000,
+            status: TransactionStatus::Success,
+            description: "Test".to_string(),
+        };
+        let mut data = Vec::new();
+        YPBankBinParser::write(&[transaction], &mut data).unwrap();
+        data[16] = 7;
+        let error = Parser::parse(Cursor::new(&data), Format::Binary).unwrap_err();
+        let report = render(&error, "input.bin", &data);
+        assert!(report.contains("byte offset 16, field TX_TYPE"));
+        assert!(report.contains("  | 00000000  59 50 42 4e"));
+        assert!(report.contains("\n  | 00000010  07 "));
+        assert!(report.contains(&format!("\n  | {}^^\n", " ".repeat(10))));
+    }
+
+    /// Checks if an offset far past the end of the data is rendered without overflow
+    #[test]
+    fn test_render_offset_past_end() {
+        let data: Vec<u8> = (0..40).collect();
+        let error = ParseError::new(ParseErrorKind::InvalidData("truncated record".to_string()))
+            .with_location(Location { byte_offset: Some(u64::MAX), ..Default::default() });
+        let report = render(&error, "input.bin", &data);
+        assert!(report.contains("\n  | 00000010  10 11 "));
+        assert!(report.contains("\n  | 00000020  20 21 "));
+        assert!(!report.contains('^'));
+        assert!(report.contains(&format!("offset {} is past the end of input (40 bytes)", u64::MAX)));
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::{ParseError, ParseErrorKind};
+    use financial_parser::format::registry::{find_format, find_input_format, register_format, FormatRegistry};
+    use financial_parser::format::{Format, TransactionFormat, TransactionIter, TransactionWriter};
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::parser::Parser;
+    use std::io::{BufRead, BufReader, Cursor, Read, Write};
+    use std::str::FromStr;
+
+    /// In-house format: one transaction per line, fields separated with '|'
+    struct PipeFormat;
+
+    struct PipeWriter<'a> {
+        writer: Box<dyn Write + 'a>,
+    }
+
+    impl TransactionWriter for PipeWriter<'_> {
+        fn write_one(&mut self, t: &Transaction) -> Result<(), ParseError> {
+            writeln!(
+                self.writer,
+                "{}|{}|{}|{}|{}|{}|{}|{}",
+                t.tx_id, t.tx_type, t.from_user_id, t.to_user_id, t.amount.minor_units, t.timestamp, t.status, t.description
+            )?;
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<(), ParseError> {
+            self.writer.flush()?;
+            Ok(())
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<Transaction, ParseError> {
+        let fields: Vec<&str> = line.splitn(8, '|').collect();
+        let invalid = || ParseError::new(ParseErrorKind::InvalidData(line.to_string()));
+        if fields.len() != 8 {
+            return Err(invalid());
+        }
+        Ok(Transaction {
+            tx_id: fields[0].parse().map_err(|_| invalid())?,
+            tx_type: TransactionType::from_str(fields[1]).map_err(|_| invalid())?,
+            from_user_id: fields[2].parse().map_err(|_| invalid())?,
+            to_user_id: fields[3].parse().map_err(|_| invalid())?,
+            amount: Money::new(fields[4].parse().map_err(|_| invalid())?, CurrencyCode::USD),
+            timestamp: fields[5].parse().map_err(|_| invalid())?,
+            status: TransactionStatus::from_str(fields[6]).map_err(|_| invalid())?,
+            description: fields[7].to_string(),
+        })
+    }
+
+    impl TransactionFormat for PipeFormat {
+        fn name(&self) -> &str {
+            "pipe"
+        }
+
+        fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+            Box::new(BufReader::new(reader).lines().map(|line| parse_line(&line?)))
+        }
+
+        fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+            Box::new(PipeWriter { writer })
+        }
+    }
+
+    fn sample_transactions() -> Vec<Transaction> {
+        vec![Transaction {
+            tx_id: 1001,
+            tx_type: TransactionType::Transfer,
+            from_user_id: 501,
+            to_user_id: 502,
+            amount: Money::new(15000, CurrencyCode::USD),
+            timestamp: 1672534800000,
+            status: TransactionStatus::Pending,
+            description: "Payment".to_string(),
+        }]
+    }
+
+    /// Checks if built-in formats are registered and looked up case-insensitively
+    #[test]
+    fn test_builtin_formats() {
+        let registry = FormatRegistry::default();
+        assert_eq!(registry.names(), vec!["text", "csv", "binary", "json", "jsonl"]);
+        assert_eq!(registry.get("CSV").unwrap().name(), "csv");
+        assert!(matches!(registry.get("xml").unwrap_err().kind(), ParseErrorKind::UnknownFormat(..)));
+    }
+
+    /// Checks if a custom format works through the global registry and Parser
+    #[test]
+    fn test_custom_format_round_trip() {
+        register_format(PipeFormat);
+        let pipe = find_format("pipe").unwrap();
+        let transactions = sample_transactions();
+        let mut output = Vec::new();
+        Parser::write(&transactions, &mut output, &pipe).unwrap();
+        assert_eq!(
+            String::from_utf8(output.clone()).unwrap(),
+            "1001|TRANSFER|501|502|15000|1672534800000|PENDING|Payment\n"
+        );
+        let parsed = Parser::parse(Cursor::new(output), &pipe).unwrap();
+        assert_eq!(parsed, transactions);
+        let mut binary = Vec::new();
+        Parser::write(&parsed, &mut binary, Format::Binary).unwrap();
+        assert_eq!(Parser::parse(Cursor::new(binary), Format::Binary).unwrap(), transactions);
+    }
+
+    /// Checks if built-in formats are detected by content
+    #[test]
+    fn test_detect_format() {
+        let transactions = sample_transactions();
+        for format in [Format::Text, Format::Csv, Format::Binary, Format::Json, Format::JsonLines] {
+            let mut output = Vec::new();
+            Parser::write(&transactions, &mut output, format).unwrap();
+            let mut reader = BufReader::new(Cursor::new(output));
+            assert_eq!(Parser::detect_format(&mut reader), Some(format));
+            assert_eq!(Parser::parse(reader, format).unwrap(), transactions);
+        }
+        let mut unknown = BufReader::new(Cursor::new("just some words"));
+        assert_eq!(Parser::detect_format(&mut unknown), None);
+    }
+
+    /// Checks if `auto` input format reads any built-in format
+    #[test]
+    fn test_auto_format() {
+        let auto = find_input_format("auto").unwrap();
+        let transactions = sample_transactions();
+        for format in [Format::Text, Format::Csv, Format::Binary, Format::Json, Format::JsonLines] {
+            let mut output = Vec::new();
+            Parser::write(&transactions, &mut output, format).unwrap();
+            assert_eq!(Parser::parse(Cursor::new(output), &auto).unwrap(), transactions);
+        }
+        assert!(Parser::parse(Cursor::new(""), &auto).unwrap().is_empty());
+        assert!(matches!(
+            Parser::parse(Cursor::new("just some words"), &auto).unwrap_err().kind(),
+            ParseErrorKind::FormatNotDetected
+        ));
+    }
+}
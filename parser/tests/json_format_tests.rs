@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::ParseErrorKind;
+    use financial_parser::format::json_format::JsonParser;
+    use financial_parser::format::json_lines_format::JsonLinesParser;
+    use financial_parser::format::Format;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::parser::{OnError, ParseOptions, Parser};
+    use indoc::indoc;
+    use std::io::Cursor;
+
+    fn sample_transactions() -> Vec<Transaction> {
+        vec![
+            Transaction {
+                tx_id: 1001,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 501,
+                amount: Money::new(50000, CurrencyCode::USD),
+                timestamp: 1672531200000,
+                status: TransactionStatus::Success,
+                description: "Initial \"account\" funding".to_string(),
+            },
+            Transaction {
+                tx_id: 1002,
+                tx_type: TransactionType::Withdrawal,
+                from_user_id: 502,
+                to_user_id: 0,
+                amount: Money::new(1000, CurrencyCode::USD),
+                timestamp: 1672538400000,
+                status: TransactionStatus::Pending,
+                description: "ATM withdrawal".to_string(),
+            },
+        ]
+    }
+
+    /// Checks if transactions written as a JSON array are parsed back
+    #[test]
+    fn test_json_round_trip() {
+        let transactions = sample_transactions();
+        let mut output = Vec::new();
+        JsonParser::write(&transactions, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.starts_with("[\n  {\"tx_id\":1001,\"tx_type\":\"DEPOSIT\""));
+        assert!(output_str.ends_with("\n]\n"));
+        assert_eq!(JsonParser::parse(Cursor::new(output_str)).unwrap(), transactions);
+    }
+
+    /// Checks if empty transaction list is written as an empty JSON array
+    #[test]
+    fn test_json_empty() {
+        let mut output = Vec::new();
+        Parser::write(&[], &mut output, Format::Json).unwrap();
+        assert_eq!(output, b"[]\n");
+        assert!(Parser::parse(Cursor::new(output), Format::Json).unwrap().is_empty());
+    }
+
+    /// Checks if JSON elements go through the same validation as the other formats
+    #[test]
+    fn test_json_validation() {
+        let data = indoc! {r#"
+            [
+              {"tx_id":1,"tx_type":"DEPOSIT","from_user_id":7,"to_user_id":501,"amount":100,"timestamp":1,"status":"SUCCESS","description":"a"},
+              {"tx_id":2,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":100,"timestamp":1,"status":"DONE","description":"b"}
+            ]
+        "#};
+        let results: Vec<_> = JsonParser::iter(Cursor::new(data)).collect();
+        assert_eq!(results.len(), 2);
+        let error = results[0].as_ref().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidUserId("FROM_USER_ID", 7, TransactionType::Deposit)));
+        assert_eq!(error.location().record_index, Some(0));
+        let error = results[1].as_ref().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::Json(_)));
+        assert!(error.is_recoverable());
+        assert_eq!(error.location().record_index, Some(1));
+    }
+
+    /// Checks if malformed JSON array stops parsing
+    #[test]
+    fn test_json_syntax_error() {
+        let mut iter = JsonParser::iter(Cursor::new("[{\"tx_id\": 1,"));
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::Json(_)));
+        assert!(!error.is_recoverable());
+        assert!(iter.next().is_none());
+    }
+
+    /// Checks if transactions written as JSON Lines are parsed back
+    #[test]
+    fn test_json_lines_round_trip() {
+        let transactions = sample_transactions();
+        let mut output = Vec::new();
+        JsonLinesParser::write(&transactions, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(output_str.lines().count(), 2);
+        assert_eq!(Parser::parse(Cursor::new(output_str), Format::JsonLines).unwrap(), transactions);
+    }
+
+    /// Checks if JSON Lines errors point at the line and invalid lines are skipped
+    #[test]
+    fn test_json_lines_invalid_record() {
+        let data = indoc! {r#"
+            {"tx_id":1,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":100,"timestamp":1,"status":"SUCCESS","description":"a"}
+
+            {"tx_id":2,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":-5,"timestamp":1,"status":"SUCCESS","description":"b"}
+            {"tx_id":3,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":300,"timestamp":1,"status":"SUCCESS","description":"c"}
+        "#};
+        let mut iter = JsonLinesParser::iter(Cursor::new(data));
+        assert_eq!(iter.next().unwrap().unwrap().tx_id, 1);
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidAmount(-5)));
+        assert_eq!(error.location().line, Some(3));
+        assert_eq!(error.location().record_index, Some(1));
+        assert_eq!(iter.next().unwrap().unwrap().tx_id, 3);
+        assert!(iter.next().is_none());
+    }
+
+    /// Checks if a JSON Lines line with a syntax error is skipped in lenient modes
+    #[test]
+    fn test_json_lines_syntax_error() {
+        let data = indoc! {r#"
+            {"tx_id":1,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":100,"timestamp":1,"status":"SUCCESS","description":"a"}
+            {"tx_id":2,"tx_type":"DEPOSIT",
+            {"tx_id":3,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":300,"timestamp":1,"status":"SUCCESS","description":"c"}
+        "#};
+        let options = ParseOptions { on_error: OnError::Collect, ..ParseOptions::default() };
+        let report = Parser::parse_with_options(Cursor::new(data), Format::JsonLines, &options).unwrap();
+        let ids: Vec<u64> = report.transactions.iter().map(|t| t.tx_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(report.errors.len(), 1);
+        let error = &report.errors[0].error;
+        assert!(matches!(error.kind(), ParseErrorKind::JsonLine(_)));
+        assert_eq!(error.location().line, Some(2));
+        assert_eq!(report.errors[0].record_index, 1);
+        assert!(Parser::parse(Cursor::new(data), Format::JsonLines).is_err());
+    }
+}
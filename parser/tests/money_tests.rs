@@ -0,0 +1,194 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::ParseErrorKind;
+    use financial_parser::format::yp_bank_bin_format::{YPBankBinParser, VERSION_2};
+    use financial_parser::format::registry::AutoFormat;
+    use financial_parser::format::{Format, ReadOptions, TransactionWriter, WriteOptions};
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::{Money, MAX_SCALE};
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::parser::Parser;
+    use indoc::indoc;
+    use std::io::Cursor;
+    use std::str::FromStr;
+
+    fn transaction(amount: Money) -> Transaction {
+        Transaction {
+            tx_id: 1,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 501,
+            amount,
+            timestamp: 1672531200000,
+            status: TransactionStatus::Success,
+            description: "Test".to_string(),
+        }
+    }
+
+    /// Checks if currency codes are looked up in the ISO 4217 table
+    #[test]
+    fn test_currency_code() {
+        let jpy = CurrencyCode::from_str("jpy").unwrap();
+        assert_eq!(jpy.as_str(), "JPY");
+        assert_eq!(jpy.minor_unit(), 0);
+        assert_eq!(CurrencyCode::from_str("KWD").unwrap().minor_unit(), 3);
+        assert_eq!(CurrencyCode::USD.info().numeric, 840);
+        let error = CurrencyCode::from_str("XYZ").unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidCurrency(code) if code == "XYZ"));
+    }
+
+    /// Checks if money is formatted and rescaled according to its scale
+    #[test]
+    fn test_money_scale() {
+        let money = Money::new(12345, CurrencyCode::USD);
+        assert_eq!(money.scale, 2);
+        assert_eq!(money.to_string(), "123.45 USD");
+        assert_eq!(Money::new(-5, CurrencyCode::EUR).to_string(), "-0.05 EUR");
+        assert_eq!(Money::new(500, CurrencyCode::from_str("JPY").unwrap()).to_string(), "500 JPY");
+        let precise = money.rescale(4).unwrap();
+        assert_eq!(precise.minor_units, 1234500);
+        assert_eq!(precise.currency_minor_units(), Some(12345));
+        assert_eq!(Money::with_scale(12345, CurrencyCode::USD, 3).unwrap().currency_minor_units(), None);
+    }
+
+    /// Checks if currency survives round trip through every built-in format
+    #[test]
+    fn test_currency_round_trip() {
+        let transactions = vec![
+            transaction(Money::new(700, CurrencyCode::from_str("JPY").unwrap())),
+            transaction(Money::with_scale(123456, CurrencyCode::EUR, 4).unwrap()),
+        ];
+        for format in [Format::Binary, Format::Json, Format::JsonLines] {
+            let mut output = Vec::new();
+            Parser::write(&transactions, &mut output, format).unwrap();
+            assert_eq!(Parser::parse(Cursor::new(output), format).unwrap(), transactions);
+        }
+        for format in [Format::Text, Format::Csv] {
+            let mut output = Vec::new();
+            Parser::write(&transactions[..1], &mut output, format).unwrap();
+            assert_eq!(Parser::parse(Cursor::new(output), format).unwrap(), transactions[..1]);
+        }
+    }
+
+    /// Checks if transactions in the default currency, followed by another currency, round trip
+    #[test]
+    fn test_mixed_currency_round_trip() {
+        let transactions = vec![
+            transaction(Money::new(100, CurrencyCode::USD)),
+            transaction(Money::new(200, CurrencyCode::EUR)),
+        ];
+        for format in [Format::Binary, Format::Csv] {
+            let mut output = Vec::new();
+            Parser::write(&transactions, &mut output, format).unwrap();
+            assert_eq!(Parser::parse(Cursor::new(output), format).unwrap(), transactions);
+
+            // a streaming writer does not know the second currency in advance
+            let mut writer = Parser::writer(Vec::new(), format);
+            writer.write_one(&transactions[0]).unwrap();
+            let error = writer.write_one(&transactions[1]).unwrap_err();
+            assert!(matches!(error.kind(), ParseErrorKind::Validation(_)));
+
+            let mut output = Vec::new();
+            {
+                let options = WriteOptions { currencies: true };
+                let mut writer = Parser::writer_with_options(&mut output, format, &options);
+                for transaction in &transactions {
+                    writer.write_one(transaction).unwrap();
+                }
+                writer.finish().unwrap();
+            }
+            assert_eq!(Parser::parse(Cursor::new(output), format).unwrap(), transactions);
+        }
+    }
+
+    /// Checks if text and CSV formats reject amounts, which do not fit currency minor units
+    #[test]
+    fn test_currency_scale_mismatch() {
+        let transactions = vec![transaction(Money::with_scale(123456, CurrencyCode::EUR, 4).unwrap())];
+        for format in [Format::Text, Format::Csv] {
+            let error = Parser::write(&transactions, Vec::new(), format).unwrap_err();
+            assert!(matches!(error.kind(), ParseErrorKind::Validation(_)));
+        }
+    }
+
+    /// Checks if inputs without currency are read in the configurable default currency
+    #[test]
+    fn test_default_currency() {
+        let text = indoc! {r#"
+            TX_ID: 1
+            TX_TYPE: DEPOSIT
+            FROM_USER_ID: 0
+            TO_USER_ID: 501
+            AMOUNT: 100
+            TIMESTAMP: 1672531200000
+            STATUS: SUCCESS
+            DESCRIPTION: "Test"
+        "#};
+        let csv = indoc! {r#"
+            TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+            1,DEPOSIT,0,501,100,1672531200000,SUCCESS,"Test"
+        "#};
+        let json = r#"[{"tx_id":1,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":100,"timestamp":1672531200000,"status":"SUCCESS","description":"Test"}]"#;
+        let mut binary = Vec::new();
+        YPBankBinParser::write(&[transaction(Money::new(100, CurrencyCode::USD))], &mut binary).unwrap();
+        let expected = vec![transaction(Money::new(100, CurrencyCode::USD))];
+        assert_eq!(Parser::parse(Cursor::new(text), Format::Text).unwrap(), expected);
+        let options = ReadOptions { default_currency: CurrencyCode::RUB, ..ReadOptions::default() };
+        let parse = |data: &[u8], format: Format| -> Vec<Transaction> {
+            Parser::iter_with_options(Cursor::new(data.to_vec()), format, &options)
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        let expected = vec![transaction(Money::new(100, CurrencyCode::RUB))];
+        assert_eq!(parse(text.as_bytes(), Format::Text), expected);
+        assert_eq!(parse(csv.as_bytes(), Format::Csv), expected);
+        assert_eq!(parse(json.as_bytes(), Format::Json), expected);
+        assert_eq!(parse(&json.as_bytes()[1..json.len() - 1], Format::JsonLines), expected);
+        assert_eq!(parse(&binary, Format::Binary), expected);
+        assert_eq!(Parser::iter_with_options(Cursor::new(csv), AutoFormat, &options).collect::<Result<Vec<_>, _>>().unwrap(), expected);
+        // explicit currency is kept
+        let json = json.replace(r#""amount":100"#, r#""amount":{"minor_units":100,"currency":"EUR"}"#);
+        assert_eq!(parse(json.as_bytes(), Format::Json), vec![transaction(Money::new(100, CurrencyCode::EUR))]);
+        // other readers are not affected
+        assert_eq!(Parser::parse(Cursor::new(text), Format::Text).unwrap()[0].amount.currency, CurrencyCode::USD);
+    }
+
+    /// Checks if unknown currency in CSV is reported with its field
+    #[test]
+    fn test_invalid_currency() {
+        let csv = indoc! {r#"
+            TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION,CURRENCY
+            1,DEPOSIT,0,501,100,1672531200000,SUCCESS,"Test",ABC
+        "#};
+        let error = Parser::parse(Cursor::new(csv), Format::Csv).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidCurrency(code) if code == "ABC"));
+        assert_eq!(error.location().field.as_deref(), Some("CURRENCY"));
+        assert_eq!(error.location().line, Some(2));
+    }
+
+    /// Checks if scales above the maximum are rejected and never break formatting
+    #[test]
+    fn test_scale_limit() {
+        assert!(Money::with_scale(1, CurrencyCode::USD, MAX_SCALE).is_ok());
+        let error = Money::with_scale(1, CurrencyCode::USD, MAX_SCALE + 1).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidData(_)));
+        let money = Money { minor_units: 1, currency: CurrencyCode::USD, scale: 60 };
+        assert_eq!(money.to_string(), "1e-60 USD");
+
+        let json = r#"[{"tx_id":1,"tx_type":"DEPOSIT","from_user_id":0,"to_user_id":501,"amount":{"minor_units":1,"currency":"USD","scale":60},"timestamp":1672531200000,"status":"SUCCESS","description":"Test"}]"#;
+        assert!(Parser::parse(Cursor::new(json), Format::Json).is_err());
+
+        let mut data = Vec::new();
+        let mut writer = YPBankBinParser::writer(&mut data).with_version(VERSION_2).unwrap();
+        writer.write_one(&transaction(Money::new(100, CurrencyCode::USD))).unwrap();
+        writer.finish().unwrap();
+        let scale_offset = data.len() - 1;
+        data[scale_offset] = 60;
+        let error = Parser::parse(Cursor::new(data), Format::Binary).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidData(_)));
+        assert_eq!(error.location().field.as_deref(), Some("SCALE"));
+        assert_eq!(error.location().byte_offset, Some(scale_offset as u64));
+    }
+}
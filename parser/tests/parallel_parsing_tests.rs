@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::format::yp_bank_bin_format::YPBankBinParser;
+    use financial_parser::format::{Format, TransactionFormat, TransactionWriter};
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::parser::Parser;
+    use std::io::Read;
+
+    fn transactions(count: u64) -> Vec<Transaction> {
+        (1..=count)
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 501,
+                amount: Money::new(tx_id as i64, CurrencyCode::USD),
+                timestamp: 1672531200000,
+                status: TransactionStatus::Success,
+                description: format!("Deposit {}", tx_id),
+            })
+            .collect()
+    }
+
+    fn write(transactions: &[Transaction], format: Format) -> Vec<u8> {
+        let mut output = Vec::new();
+        Parser::write(transactions, &mut output, format).unwrap();
+        output
+    }
+
+    /// Parses every chunk separately and joins the results
+    fn parse_chunks(data: &[u8], format: Format, max_chunks: usize) -> Vec<Transaction> {
+        let chunks = format.split(data, max_chunks).unwrap();
+        assert!(chunks.len() > 1 && chunks.len() <= max_chunks);
+        assert_eq!(chunks.last().unwrap().data.as_ptr_range().end, data.as_ptr_range().end);
+        chunks
+            .iter()
+            .flat_map(|chunk| Parser::parse(chunk.prefix.chain(chunk.data), format).unwrap())
+            .collect()
+    }
+
+    /// Checks if text and binary inputs are split into record-aligned chunks
+    #[test]
+    fn test_split_record_aligned() {
+        let transactions = transactions(20);
+        for format in [Format::Text, Format::Binary] {
+            assert_eq!(parse_chunks(&write(&transactions, format), format, 4), transactions);
+        }
+        let mut data = Vec::new();
+        let mut writer = YPBankBinParser::writer(&mut data).with_checksums().with_index();
+        for transaction in &transactions {
+            writer.write_one(transaction).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(parse_chunks(&data, Format::Binary, 3), transactions);
+        assert!(Format::Csv.split(&write(&transactions, Format::Csv), 4).is_none());
+    }
+
+    /// Checks if parallel parsing keeps the input order and locates errors in the whole input
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parse_parallel() {
+        use std::io::Cursor;
+
+        let transactions = transactions(200);
+        for format in [Format::Text, Format::Binary, Format::Csv] {
+            let data = write(&transactions, format);
+            assert_eq!(Parser::parse_parallel(Cursor::new(data), format).unwrap(), transactions);
+        }
+
+        let mut text = String::from_utf8(write(&transactions, Format::Text)).unwrap();
+        let broken = text.rfind("STATUS: SUCCESS").unwrap();
+        text.replace_range(broken..broken + 15, "STATUS: DONE");
+        let expected = Parser::parse(Cursor::new(&text), Format::Text).unwrap_err();
+        let error = Parser::parse_parallel(Cursor::new(&text), Format::Text).unwrap_err();
+        assert_eq!(error.location(), expected.location());
+        assert_eq!(error.location().record_index, Some(199));
+
+        let mut data = write(&transactions, Format::Binary);
+        // STATUS of the last record precedes DESC_LEN and the description
+        let broken = data.len() - transactions[199].description.len() - 4 - 1;
+        data[broken] = 0xFF;
+        let expected = Parser::parse(Cursor::new(&data), Format::Binary).unwrap_err();
+        let error = Parser::parse_parallel(Cursor::new(&data), Format::Binary).unwrap_err();
+        assert_eq!(error.location(), expected.location());
+        assert_eq!(error.location().field.as_deref(), Some("STATUS"));
+    }
+}
@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::ParseErrorKind;
+    use financial_parser::format::yp_bank_bin_format::{YPBankBinParser, MAGIC};
+    use financial_parser::format::{Format, TransactionFormat, TransactionIter, TransactionWriter};
+    use financial_parser::parser::{OnError, ParseOptions, Parser};
+    use indoc::indoc;
+    use std::io::{Cursor, Read, Write};
+
+    const CSV_WITH_INVALID_RECORDS: &str = indoc! {r#"
+        TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+        1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding"
+        1002,TRANSFER,501,502,-15000,1672534800000,FAILURE,"Negative amount"
+        1003,WITHDRAWAL,502,0,1000,1672538400000,PENDING,"ATM withdrawal"
+        1004,UNKNOWN,502,0,1000,1672538400000,PENDING,"Unknown type"
+    "#};
+
+    fn options(on_error: OnError) -> ParseOptions {
+        ParseOptions { on_error, ..ParseOptions::default() }
+    }
+
+    /// Checks if `Fail` mode returns the first error
+    #[test]
+    fn test_fail_mode() {
+        let result = Parser::parse_with_options(
+            Cursor::new(CSV_WITH_INVALID_RECORDS),
+            Format::Csv,
+            &options(OnError::Fail),
+        );
+        assert!(matches!(result.unwrap_err().kind(), ParseErrorKind::InvalidAmount(-15000)));
+    }
+
+    /// Checks if `Skip` mode returns only valid transactions
+    #[test]
+    fn test_skip_mode() {
+        let report = Parser::parse_with_options(
+            Cursor::new(CSV_WITH_INVALID_RECORDS),
+            Format::Csv,
+            &options(OnError::Skip),
+        )
+        .unwrap();
+        let ids: Vec<u64> = report.transactions.iter().map(|t| t.tx_id).collect();
+        assert_eq!(ids, vec![1001, 1003]);
+        assert!(report.errors.is_empty());
+    }
+
+    /// Checks if `Collect` mode reports index of every rejected record
+    #[test]
+    fn test_collect_mode() {
+        let report = Parser::parse_with_options(
+            Cursor::new(CSV_WITH_INVALID_RECORDS),
+            Format::Csv,
+            &options(OnError::Collect),
+        )
+        .unwrap();
+        assert_eq!(report.transactions.len(), 2);
+        let indexes: Vec<usize> = report.errors.iter().map(|e| e.record_index).collect();
+        assert_eq!(indexes, vec![1, 3]);
+        assert!(matches!(report.errors[0].error.kind(), ParseErrorKind::InvalidAmount(-15000)));
+        assert_eq!(report.errors[0].location().line, Some(3));
+        assert_eq!(report.errors[0].location().field.as_deref(), Some("AMOUNT"));
+    }
+
+    /// Checks if binary record with invalid content is skipped using its record size
+    #[test]
+    fn test_collect_mode_binary() {
+        let mut data = Vec::new();
+        for (tx_id, tx_type) in [(1u64, 0u8), (2, 7), (3, 0)] {
+            data.extend_from_slice(&MAGIC);
+            data.extend_from_slice(&46u32.to_be_bytes());
+            data.extend_from_slice(&tx_id.to_be_bytes());
+            data.push(tx_type);
+            data.extend_from_slice(&0u64.to_be_bytes());
+            data.extend_from_slice(&501u64.to_be_bytes());
+            data.extend_from_slice(&100i64.to_be_bytes());
+            data.extend_from_slice(&1672531200000u64.to_be_bytes());
+            data.push(0);
+            data.extend_from_slice(&4u32.to_be_bytes());
+            data.extend_from_slice(b"Test");
+        }
+        let report = Parser::parse_with_options(
+            Cursor::new(data.clone()),
+            Format::Binary,
+            &options(OnError::Collect),
+        )
+        .unwrap();
+        let ids: Vec<u64> = report.transactions.iter().map(|t| t.tx_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].record_index, 1);
+        assert_eq!(report.errors[0].location().byte_offset, Some(58 + 16));
+        assert_eq!(report.errors[0].location().field.as_deref(), Some("TX_TYPE"));
+        data[0] = b'X';
+        let result = Parser::parse_with_options(Cursor::new(data), Format::Binary, &options(OnError::Collect));
+        assert!(matches!(result.unwrap_err().kind(), ParseErrorKind::InvalidMagic(..)));
+    }
+
+    /// Binary format, which resynchronizes after corrupted record boundaries
+    struct ResyncBinary;
+
+    impl TransactionFormat for ResyncBinary {
+        fn name(&self) -> &str {
+            "resync-binary"
+        }
+
+        fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionIter<'a> {
+            Box::new(YPBankBinParser::iter(reader).with_resync())
+        }
+
+        fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+            Box::new(YPBankBinParser::writer(writer))
+        }
+    }
+
+    /// Checks if bytes, skipped while resynchronizing, do not shift record indexes
+    #[test]
+    fn test_collect_mode_skipped_bytes() {
+        let mut data = Vec::new();
+        for (tx_id, amount) in [(1u64, 100i64), (2, 0), (3, 100)] {
+            if tx_id == 2 {
+                data.extend_from_slice(&[0xFF, 0x00, 0x59]);
+            }
+            data.extend_from_slice(&MAGIC);
+            data.extend_from_slice(&46u32.to_be_bytes());
+            data.extend_from_slice(&tx_id.to_be_bytes());
+            data.push(0);
+            data.extend_from_slice(&0u64.to_be_bytes());
+            data.extend_from_slice(&501u64.to_be_bytes());
+            data.extend_from_slice(&amount.to_be_bytes());
+            data.extend_from_slice(&1672531200000u64.to_be_bytes());
+            data.push(0);
+            data.extend_from_slice(&4u32.to_be_bytes());
+            data.extend_from_slice(b"Test");
+        }
+        let report = Parser::parse_with_options(Cursor::new(data), ResyncBinary, &options(OnError::Collect)).unwrap();
+        let ids: Vec<u64> = report.transactions.iter().map(|t| t.tx_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(report.errors.len(), 2);
+        assert!(matches!(report.errors[0].error.kind(), ParseErrorKind::SkippedBytes(58, 61)));
+        assert_eq!(report.errors[0].record_index, 1);
+        assert_eq!(report.errors[0].location().record_index, None);
+        assert_eq!(report.errors[1].location().field.as_deref(), Some("AMOUNT"));
+        assert_eq!(report.errors[1].record_index, 1);
+        assert_eq!(report.errors[1].location().record_index, Some(1));
+    }
+
+    /// Checks if errors breaking the whole input are returned in lenient modes
+    #[test]
+    fn test_invalid_header_is_fatal() {
+        let result = Parser::parse_with_options(
+            Cursor::new("ID,TYPE\n1,DEPOSIT\n"),
+            Format::Csv,
+            &options(OnError::Skip),
+        );
+        assert!(matches!(result.unwrap_err().kind(), ParseErrorKind::InvalidHeader(..)));
+    }
+}
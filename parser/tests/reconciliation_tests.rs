@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::reconciliation::{
+        reconcile, reconcile_with_policy, ComparePolicy, FieldDiff, TransactionField,
+    };
+
+    fn transaction(tx_id: u64, amount: i64) -> Transaction {
+        Transaction {
+            tx_id,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 501,
+            amount: Money::new(amount, CurrencyCode::USD),
+            timestamp: 1672531200000,
+            status: TransactionStatus::Success,
+            description: format!("Deposit {}", tx_id),
+        }
+    }
+
+    /// Checks if the order of transactions does not matter
+    #[test]
+    fn test_reconcile_reordered() {
+        let left = vec![transaction(1, 100), transaction(2, 200), transaction(3, 300)];
+        let right = vec![transaction(3, 300), transaction(1, 100), transaction(2, 200)];
+        let reconciliation = reconcile(&left, &right);
+        assert!(reconciliation.is_identical());
+        assert_eq!(reconciliation.matched, 3);
+    }
+
+    /// Checks if an inserted record is reported alone and fields are compared by ID
+    #[test]
+    fn test_reconcile_differences() {
+        let left = vec![transaction(1, 100), transaction(2, 200), transaction(3, 300)];
+        let mut changed = transaction(3, 350);
+        changed.status = TransactionStatus::Failure;
+        let right = vec![transaction(1, 100), transaction(9, 900), transaction(2, 200), changed];
+        let reconciliation = reconcile(&left, &right);
+        assert!(!reconciliation.is_identical());
+        assert!(reconciliation.left_only.is_empty());
+        assert_eq!(reconciliation.right_only, vec![transaction(9, 900)]);
+        assert_eq!(reconciliation.matched, 2);
+        assert_eq!(reconciliation.different.len(), 1);
+        assert_eq!(reconciliation.different[0].left.tx_id, 3);
+        assert_eq!(reconciliation.different[0].fields, vec![
+            FieldDiff { field: "AMOUNT", left: "3.00 USD".to_string(), right: "3.50 USD".to_string() },
+            FieldDiff { field: "STATUS", left: "SUCCESS".to_string(), right: "FAILURE".to_string() },
+        ]);
+
+        let reconciliation = reconcile(&right[..1], &left);
+        assert_eq!(reconciliation.right_only.len(), 2);
+        assert_eq!(reconcile(&left, &[]).left_only, left);
+    }
+
+    /// Checks if the comparison policy ignores fields, tolerates timestamps and normalizes descriptions
+    #[test]
+    fn test_reconcile_with_policy() {
+        let left = vec![transaction(1, 100), transaction(2, 200)];
+        let mut first = transaction(1, 100);
+        first.timestamp += 400;
+        first.description = "  deposit   1 ".to_string();
+        let mut second = transaction(2, 200);
+        second.description = "Other wording".to_string();
+        let right = vec![first, second];
+        assert_eq!(reconcile(&left, &right).different.len(), 2);
+
+        let mut policy = ComparePolicy {
+            timestamp_tolerance: 500,
+            ignore_description_case: true,
+            ignore_description_whitespace: true,
+            ..ComparePolicy::default()
+        };
+        let reconciliation = reconcile_with_policy(&left, &right, &policy);
+        assert_eq!(reconciliation.matched, 1);
+        assert_eq!(reconciliation.different.len(), 1);
+        assert_eq!(reconciliation.different[0].fields[0].field, "DESCRIPTION");
+
+        policy.timestamp_tolerance = 399;
+        policy.ignored_fields = vec![TransactionField::Description];
+        let reconciliation = reconcile_with_policy(&left, &right, &policy);
+        assert_eq!(reconciliation.matched, 1);
+        assert_eq!(reconciliation.different[0].fields[0].field, "TIMESTAMP");
+        assert_eq!(TransactionField::FromUserId.name(), "FROM_USER_ID");
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::ParseErrorKind;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::{Transaction, TransactionBuilder};
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+
+    fn deposit() -> TransactionBuilder {
+        Transaction::builder()
+            .with_tx_id(1)
+            .with_tx_type(TransactionType::Deposit)
+            .with_to_user_id(501)
+            .with_amount(Money::new(100, CurrencyCode::USD))
+            .with_timestamp(1672531200000)
+            .with_status(TransactionStatus::Success)
+            .with_description("Test")
+    }
+
+    /// Checks if builder constructs a valid transaction
+    #[test]
+    fn test_build_transaction() {
+        let transaction = deposit().build().unwrap();
+        assert_eq!(
+            transaction,
+            Transaction {
+                tx_id: 1,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 501,
+                amount: Money::new(100, CurrencyCode::USD),
+                timestamp: 1672531200000,
+                status: TransactionStatus::Success,
+                description: "Test".to_string(),
+            }
+        );
+    }
+
+    /// Checks if builder rejects missing fields, non-positive amounts and wrong user ids
+    #[test]
+    fn test_build_invalid_transaction() {
+        let error = Transaction::builder().with_tx_id(1).build().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::MissingField(field) if field == "TX_TYPE"));
+
+        let error = deposit().with_amount(Money::new(0, CurrencyCode::USD)).build().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidAmount(0)));
+        assert_eq!(error.location().field.as_deref(), Some("AMOUNT"));
+
+        let error = deposit().with_from_user_id(7).build().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidUserId("FROM_USER_ID", 7, TransactionType::Deposit)));
+
+        let error = deposit().with_tx_type(TransactionType::Withdrawal).with_from_user_id(501).build().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidUserId("TO_USER_ID", 501, TransactionType::Withdrawal)));
+    }
+
+    /// Checks if transaction is deserialized back from its serialized form
+    #[test]
+    fn test_deserialize_transaction() {
+        let transaction = deposit().build().unwrap();
+        let json = serde_json::to_string(&transaction).unwrap();
+        assert!(json.contains(r#""tx_type":"DEPOSIT""#));
+        assert_eq!(serde_json::from_str::<Transaction>(&json).unwrap(), transaction);
+        let status: TransactionStatus = serde_json::from_str(r#""PENDING""#).unwrap();
+        assert_eq!(status, TransactionStatus::Pending);
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::{ParseError, ParseErrorKind};
+    use financial_parser::format::yp_bank_bin_format::YPBankBinParser;
+    use financial_parser::format::Format;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use financial_parser::parser::{OnError, ParseOptions, Parser};
+    use financial_parser::validation::{
+        DescriptionRule, FnRule, NonSelfTransfer, PositiveAmount, Rule, Severity, TimestampRange, UserIds, Validator,
+    };
+    use indoc::indoc;
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    fn transfer() -> Transaction {
+        Transaction {
+            tx_id: 1,
+            tx_type: TransactionType::Transfer,
+            from_user_id: 501,
+            to_user_id: 502,
+            amount: Money::new(100, CurrencyCode::USD),
+            timestamp: 1672531200000,
+            status: TransactionStatus::Success,
+            description: "Test".to_string(),
+        }
+    }
+
+    /// Checks if built-in rules report violations with their fields
+    #[test]
+    fn test_builtin_rules() {
+        let valid = transfer();
+        let rules: [&dyn Rule; 5] = [
+            &PositiveAmount,
+            &UserIds,
+            &NonSelfTransfer,
+            &TimestampRange { min: 1672531200000, max: 1704067199999 },
+            &DescriptionRule::default(),
+        ];
+        for rule in rules {
+            assert!(rule.check(&(&valid).into()).is_ok(), "{}", rule.name());
+        }
+        let cases = [
+            (Transaction { amount: Money::new(0, CurrencyCode::USD), ..transfer() }, rules[0], "AMOUNT"),
+            (Transaction { tx_type: TransactionType::Deposit, ..transfer() }, rules[1], "FROM_USER_ID"),
+            (Transaction { to_user_id: 501, ..transfer() }, rules[2], "TO_USER_ID"),
+            (Transaction { timestamp: 1704067200000, ..transfer() }, rules[3], "TIMESTAMP"),
+            (Transaction { description: "a".repeat(256), ..transfer() }, rules[4], "DESCRIPTION"),
+            (Transaction { description: "Line\nbreak".to_string(), ..transfer() }, rules[4], "DESCRIPTION"),
+        ];
+        for (transaction, rule, field) in cases {
+            let error = rule.check(&(&transaction).into()).unwrap_err();
+            assert_eq!(error.location().field.as_deref(), Some(field), "{}", rule.name());
+        }
+    }
+
+    /// Checks if validator rejects on error rules and collects warning rules, including custom ones
+    #[test]
+    fn test_validator_severity() {
+        let validator = Validator::default()
+            .with_rule(NonSelfTransfer, Severity::Error)
+            .with_rule(
+                FnRule::new("round-amount", |transaction| match transaction.amount.minor_units % 100 {
+                    0 => Ok(()),
+                    _ => Err(ParseError::new(ParseErrorKind::Validation("amount is not round".to_string()))
+                        .with_field("AMOUNT")),
+                }),
+                Severity::Warning,
+            );
+        assert!(validator.validate(&transfer()).unwrap().is_empty());
+
+        let odd = Transaction { amount: Money::new(150, CurrencyCode::USD), ..transfer() };
+        let warnings = validator.validate(&odd).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].rule, "round-amount");
+        assert_eq!(warnings[0].severity, Severity::Warning);
+
+        let self_transfer = Transaction { to_user_id: 501, ..odd };
+        let error = validator.validate(&self_transfer).unwrap_err();
+        assert!(error.to_string().contains("transfer from user 501 to itself"));
+        let rules: Vec<String> = validator.check(&self_transfer).into_iter().map(|v| v.rule).collect();
+        assert_eq!(rules, vec!["non-self-transfer", "round-amount"]);
+    }
+
+    /// Checks if validator of parse options rejects records and collects warnings
+    #[test]
+    fn test_parse_with_validator() {
+        let csv = indoc! {r#"
+            TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+            1,TRANSFER,501,502,100,1672531200000,SUCCESS,"Rent"
+            2,TRANSFER,501,501,100,1672531200000,SUCCESS,"Self"
+            3,TRANSFER,501,502,100,1672531200000,SUCCESS,"Too long description"
+        "#};
+        let validator = Validator::new()
+            .with_rule(NonSelfTransfer, Severity::Error)
+            .with_rule(DescriptionRule { max_length: 10, ..DescriptionRule::default() }, Severity::Warning);
+        let options = ParseOptions {
+            on_error: OnError::Collect,
+            validator: Some(Arc::new(validator)),
+            ..ParseOptions::default()
+        };
+        let report = Parser::parse_with_options(Cursor::new(csv), Format::Csv, &options).unwrap();
+        let ids: Vec<u64> = report.transactions.iter().map(|t| t.tx_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].record_index, 1);
+        assert_eq!(report.errors[0].location().field.as_deref(), Some("TO_USER_ID"));
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].record_index, 2);
+        assert_eq!(report.warnings[0].location().record_index, Some(2));
+    }
+
+    /// Checks if binary format enforces the same requirements as the other formats
+    #[test]
+    fn test_binary_rejects_zero_amount() {
+        let transaction = Transaction { amount: Money::new(0, CurrencyCode::USD), ..transfer() };
+        let error = YPBankBinParser::write(&[transaction], &mut Vec::new()).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidAmount(0)));
+
+        let mut data = Vec::new();
+        YPBankBinParser::write(&[transfer()], &mut data).unwrap();
+        data[33..41].copy_from_slice(&0i64.to_be_bytes());
+        let error = Parser::parse(Cursor::new(data), Format::Binary).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidAmount(0)));
+        assert_eq!(error.location().field.as_deref(), Some("AMOUNT"));
+        assert_eq!(error.location().byte_offset, Some(33));
+    }
+}
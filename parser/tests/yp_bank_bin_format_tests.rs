@@ -4,7 +4,12 @@ mod tests {
     use financial_parser::model::transaction_type::TransactionType;
     use financial_parser::format::yp_bank_bin_format::MAGIC;
     use financial_parser::format::yp_bank_bin_format::YPBankBinParser;
+    use financial_parser::format::yp_bank_bin_format::{FileHeader, FILE_MAGIC, FLAG_CHECKSUMS, FLAG_INDEX, VERSION_1, VERSION_2};
+    use financial_parser::format::{TransactionWriter, WriteOptions};
+    use financial_parser::errors::ParseErrorKind;
     use std::io::Cursor;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
     use financial_parser::model::transaction::Transaction;
 
     #[test]
@@ -29,7 +34,7 @@ mod tests {
         assert_eq!(tx.tx_type, TransactionType::Deposit);
         assert_eq!(tx.from_user_id, 0);
         assert_eq!(tx.to_user_id, 501);
-        assert_eq!(tx.amount, 50000);
+        assert_eq!(tx.amount.minor_units, 50000);
         assert_eq!(tx.timestamp, 1672531200000);
         assert_eq!(tx.status, TransactionStatus::Success);
         assert_eq!(tx.description, "Test desc");
@@ -63,7 +68,7 @@ mod tests {
             tx_type: TransactionType::Deposit,
             from_user_id: 0,
             to_user_id: 456,
-            amount: 100,
+            amount: Money::new(100, CurrencyCode::USD),
             timestamp: 1633036800000,
             status: TransactionStatus::Success,
             description: "Test".to_string(),
@@ -85,4 +90,272 @@ mod tests {
             assert_eq!(orig.description, parsed.description);
         }
     }
-}
\ No newline at end of file
+
+    /// Checks if streaming reader parses written records lazily
+    #[test]
+    fn test_iter_yp_bank_bin() {
+        let transactions: Vec<Transaction> = (1..=3)
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Withdrawal,
+                from_user_id: 456,
+                to_user_id: 0,
+                amount: Money::new(100 * tx_id as i64, CurrencyCode::USD),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Pending,
+                description: format!("Withdrawal {}", tx_id),
+            })
+            .collect();
+        let mut output = Vec::new();
+        YPBankBinParser::write(&transactions, &mut output).unwrap();
+        let mut iter = YPBankBinParser::iter(Cursor::new(output));
+        for expected in &transactions {
+            assert_eq!(&iter.next().unwrap().unwrap(), expected);
+        }
+        assert!(iter.next().is_none());
+    }
+
+    /// Checks if the chosen version is written with a file header and read back
+    #[test]
+    fn test_versioned_yp_bank_bin() {
+        let transaction = Transaction {
+            tx_id: 1,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 456,
+            amount: Money::new(100, CurrencyCode::USD),
+            timestamp: 1633036800000,
+            status: TransactionStatus::Success,
+            description: "Test".to_string(),
+        };
+        let mut output = Vec::new();
+        let mut writer = YPBankBinParser::writer(&mut output).with_version(VERSION_2).unwrap();
+        writer.write_one(&transaction).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(&output[..4], &FILE_MAGIC);
+        assert_eq!(&output[4..8], &[0, 2, 0, 0]);
+        assert_eq!(&output[8..12], &MAGIC);
+
+        let mut iter = YPBankBinParser::iter(Cursor::new(&output));
+        assert_eq!(iter.next().unwrap().unwrap(), transaction);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.header(), Some(FileHeader { version: VERSION_2, flags: 0 }));
+
+        let mut legacy = Vec::new();
+        YPBankBinParser::write(std::slice::from_ref(&transaction), &mut legacy).unwrap();
+        assert_eq!(&legacy[..4], &MAGIC);
+        let mut iter = YPBankBinParser::iter(Cursor::new(&legacy));
+        assert_eq!(iter.next().unwrap().unwrap(), transaction);
+        assert_eq!(iter.header(), Some(FileHeader { version: VERSION_1, flags: 0 }));
+    }
+
+    /// Checks if unknown versions are rejected by both reader and writer
+    #[test]
+    fn test_unsupported_version() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&FILE_MAGIC);
+        data.extend_from_slice(&9u16.to_be_bytes());
+        data.extend_from_slice(&0u16.to_be_bytes());
+        let error = YPBankBinParser::parse(&mut Cursor::new(data)).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::UnsupportedVersion(9)));
+        assert_eq!(error.location().byte_offset, Some(4));
+        assert!(!error.is_recoverable());
+
+        let error = YPBankBinParser::writer(Vec::new()).with_version(0).err().unwrap();
+        assert!(matches!(error.kind(), ParseErrorKind::UnsupportedVersion(0)));
+    }
+
+    /// Checks if a corrupted amount is caught by the record checksum and the next record is still read
+    #[test]
+    fn test_checksum_mismatch() {
+        let transactions: Vec<Transaction> = (1..=2)
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 456,
+                amount: Money::new(100, CurrencyCode::USD),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Success,
+                description: "Test".to_string(),
+            })
+            .collect();
+        let mut output = Vec::new();
+        let mut writer = YPBankBinParser::writer(&mut output).with_checksums();
+        for transaction in &transactions {
+            writer.write_one(transaction).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(&output[4..8], &[0, 2, 0, FLAG_CHECKSUMS as u8]);
+        assert_eq!(YPBankBinParser::parse(&mut Cursor::new(&output)).unwrap(), transactions);
+
+        // header 8 bytes, amount at offset 33 of the record
+        output[8 + 33 + 7] ^= 0x01;
+        let record_len = (output.len() - 8) / 2;
+        let mut iter = YPBankBinParser::iter(Cursor::new(&output));
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::ChecksumMismatch(..)));
+        assert_eq!(error.location().field.as_deref(), Some("CHECKSUM"));
+        assert_eq!(error.location().byte_offset, Some((8 + record_len - 4) as u64));
+        assert_eq!(iter.next().unwrap().unwrap(), transactions[1]);
+        assert!(iter.next().is_none());
+    }
+
+    /// Checks if resynchronizing reader skips damaged bytes and reads the following records
+    #[test]
+    fn test_resync_yp_bank_bin() {
+        let transactions: Vec<Transaction> = (1..=3)
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 456,
+                amount: Money::new(100, CurrencyCode::USD),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Success,
+                description: "Test".to_string(),
+            })
+            .collect();
+        let mut output = Vec::new();
+        YPBankBinParser::write(&transactions, &mut output).unwrap();
+        let record_len = output.len() / 3;
+        // garbage between the first and the second records, broken magic of the third record
+        output.splice(record_len..record_len, [0xFF, 0x59, 0x50, 0x42, 0x00]);
+        let third_offset = 2 * record_len + 5;
+        output[third_offset] = 0;
+
+        let mut strict = YPBankBinParser::iter(Cursor::new(&output));
+        assert_eq!(strict.next().unwrap().unwrap(), transactions[0]);
+        assert!(matches!(strict.next().unwrap().unwrap_err().kind(), ParseErrorKind::InvalidMagic(..)));
+        assert!(strict.next().is_none());
+
+        let mut iter = YPBankBinParser::iter(Cursor::new(&output)).with_resync();
+        assert_eq!(iter.next().unwrap().unwrap(), transactions[0]);
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(error.is_recoverable());
+        assert!(matches!(error.kind(), ParseErrorKind::SkippedBytes(start, end)
+            if *start == record_len as u64 && *end == record_len as u64 + 5));
+        assert_eq!(iter.next().unwrap().unwrap(), transactions[1]);
+        let error = iter.next().unwrap().unwrap_err();
+        assert_eq!(error.location().byte_offset, Some(third_offset as u64));
+        assert!(iter.next().is_none());
+        assert_eq!(iter.skipped_ranges(), &[
+            record_len as u64..record_len as u64 + 5,
+            third_offset as u64..output.len() as u64,
+        ]);
+    }
+
+    /// Checks if resynchronizing reader does not trust a corrupted record size
+    #[test]
+    fn test_resync_corrupted_record_size() {
+        let transactions: Vec<Transaction> = (1..=3)
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 456,
+                amount: Money::new(100, CurrencyCode::USD),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Success,
+                description: "Test".to_string(),
+            })
+            .collect();
+        let mut output = Vec::new();
+        YPBankBinParser::write(&transactions, &mut output).unwrap();
+        let record_len = output.len() / 3;
+        // record size of the second record points into the middle of the third record
+        let size_offset = record_len + 4;
+        let record_size = u32::from_be_bytes(output[size_offset..size_offset + 4].try_into().unwrap());
+        output[size_offset..size_offset + 4].copy_from_slice(&(record_size + 3).to_be_bytes());
+
+        let mut iter = YPBankBinParser::iter(Cursor::new(&output)).with_resync();
+        assert_eq!(iter.next().unwrap().unwrap(), transactions[0]);
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::SkippedBytes(start, end)
+            if *start == record_len as u64 && *end == 2 * record_len as u64));
+        assert_eq!(iter.next().unwrap().unwrap(), transactions[2]);
+        assert!(iter.next().is_none());
+    }
+
+    /// Checks if transactions are found by ID through the index footer and without it
+    #[test]
+    fn test_get_yp_bank_bin() {
+        let transactions: Vec<Transaction> = [5u64, 3, 9, 1]
+            .into_iter()
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 456,
+                amount: Money::new(100 * tx_id as i64, CurrencyCode::USD),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Success,
+                description: format!("Deposit {}", tx_id),
+            })
+            .collect();
+        let mut indexed = Vec::new();
+        let mut writer = YPBankBinParser::writer(&mut indexed).with_checksums().with_index();
+        for transaction in &transactions {
+            writer.write_one(transaction).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(&indexed[6..8], &[0, (FLAG_CHECKSUMS | FLAG_INDEX) as u8]);
+        assert_eq!(YPBankBinParser::parse(&mut Cursor::new(&indexed)).unwrap(), transactions);
+
+        let mut plain = Vec::new();
+        YPBankBinParser::write(&transactions, &mut plain).unwrap();
+
+        for data in [indexed, plain] {
+            let mut reader = YPBankBinParser::iter(Cursor::new(data));
+            assert_eq!(reader.get(9).unwrap(), Some(transactions[2].clone()));
+            assert_eq!(reader.get(3).unwrap(), Some(transactions[1].clone()));
+            assert_eq!(reader.next().unwrap().unwrap(), transactions[2]);
+            assert_eq!(reader.get(4).unwrap(), None);
+        }
+    }
+
+    /// Checks if the default layout keeps version 1 records and currencies need version 2
+    #[test]
+    fn test_money_extension_yp_bank_bin() {
+        let transaction = |currency| Transaction {
+            tx_id: 1,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 456,
+            amount: Money::new(100, currency),
+            timestamp: 1633036800000,
+            status: TransactionStatus::Success,
+            description: "Test".to_string(),
+        };
+        let mut plain = Vec::new();
+        YPBankBinParser::write(&[transaction(CurrencyCode::USD)], &mut plain).unwrap();
+        assert_eq!(&plain[..4], &MAGIC);
+        assert_eq!(&plain[4..8], &46u32.to_be_bytes());
+        assert_eq!(plain.len(), 58);
+
+        let mut upgraded = Vec::new();
+        YPBankBinParser::write(&[transaction(CurrencyCode::EUR)], &mut upgraded).unwrap();
+        assert_eq!(&upgraded[..4], &FILE_MAGIC);
+        assert_eq!(&upgraded[4..6], &VERSION_2.to_be_bytes());
+        let parsed = YPBankBinParser::parse(&mut Cursor::new(upgraded)).unwrap();
+        assert_eq!(parsed, vec![transaction(CurrencyCode::EUR)]);
+
+        let mut writer = YPBankBinParser::writer(Vec::new()).with_version(VERSION_1).unwrap();
+        writer.write_one(&transaction(CurrencyCode::USD)).unwrap();
+        let error = writer.write_one(&transaction(CurrencyCode::EUR)).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::Validation(_)));
+
+        // explicitly chosen version is kept even for the first record
+        let mut output = Vec::new();
+        let mut writer = YPBankBinParser::writer(&mut output).with_version(VERSION_1).unwrap();
+        let error = writer.write_one(&transaction(CurrencyCode::EUR)).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::Validation(_)));
+        assert_eq!(error.location().field.as_deref(), Some("AMOUNT"));
+        writer.write_one(&transaction(CurrencyCode::USD)).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(&output[..4], &MAGIC);
+        let options = WriteOptions { currencies: true };
+        let mut writer = YPBankBinParser::writer(Vec::new()).with_version(VERSION_1).unwrap().with_options(&options);
+        assert!(writer.write_one(&transaction(CurrencyCode::EUR)).is_err());
+    }
+}
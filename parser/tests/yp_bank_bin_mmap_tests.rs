@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use financial_parser::errors::ParseErrorKind;
+    use financial_parser::format::yp_bank_bin_format::YPBankBinParser;
+    use financial_parser::format::yp_bank_bin_mmap::{YPBankBinMmap, YPBankBinSliceReader};
+    use financial_parser::format::TransactionWriter;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
+    use financial_parser::model::transaction::Transaction;
+    use financial_parser::model::transaction_ref::TransactionRef;
+    use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::model::transaction_type::TransactionType;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn transactions() -> Vec<Transaction> {
+        (1..=3)
+            .map(|tx_id| Transaction {
+                tx_id,
+                tx_type: TransactionType::Transfer,
+                from_user_id: 455,
+                to_user_id: 456,
+                amount: Money::new(100 * tx_id as i64, CurrencyCode::EUR),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Success,
+                description: format!("Transfer {}", tx_id),
+            })
+            .collect()
+    }
+
+    /// Checks if mapped file yields views with descriptions borrowed from the mapped bytes
+    #[test]
+    fn test_mmap_yp_bank_bin() {
+        let transactions = transactions();
+        let mut file = NamedTempFile::new().unwrap();
+        YPBankBinParser::write(&transactions, &mut file).unwrap();
+        file.flush().unwrap();
+
+        let mmap = YPBankBinMmap::open(file.path()).unwrap();
+        let bytes = mmap.as_bytes().as_ptr_range();
+        let views: Vec<TransactionRef> = mmap.iter().collect::<Result<_, _>>().unwrap();
+        let expected: Vec<TransactionRef> = transactions.iter().map(TransactionRef::from).collect();
+        assert_eq!(views, expected);
+        for view in &views {
+            assert!(bytes.contains(&view.description.as_ptr()));
+        }
+        let total: i64 = views.iter().map(|view| view.amount.minor_units).sum();
+        assert_eq!(total, 600);
+        assert_eq!(views[0].to_transaction(), transactions[0]);
+    }
+
+    /// Checks if slice reader handles file header, checksums and index footer
+    #[test]
+    fn test_slice_reader_checksums() {
+        let transactions = transactions();
+        let mut data = Vec::new();
+        let mut writer = YPBankBinParser::writer(&mut data).with_checksums().with_index();
+        for transaction in &transactions {
+            writer.write_one(transaction).unwrap();
+        }
+        writer.finish().unwrap();
+        let views: Vec<Transaction> = YPBankBinSliceReader::new(&data)
+            .map(|view| view.unwrap().to_transaction())
+            .collect();
+        assert_eq!(views, transactions);
+
+        // amount of the first record
+        data[8 + 33 + 7] ^= 0x01;
+        let mut reader = YPBankBinSliceReader::new(&data);
+        let error = reader.next().unwrap().unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::ChecksumMismatch(..)));
+        assert_eq!(error.location().record_index, Some(0));
+        assert_eq!(reader.next().unwrap().unwrap().tx_id, 2);
+    }
+}
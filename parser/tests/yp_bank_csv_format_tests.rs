@@ -2,7 +2,11 @@
 mod tests {
     use std::io::Cursor;
     use indoc::indoc;
-    use financial_parser::format::yp_bank_csv_format::YPBankCsvParser;
+    use financial_parser::errors::ParseErrorKind;
+    use financial_parser::format::yp_bank_csv_format::{CsvDialect, YPBankCsvParser};
+    use financial_parser::format::TransactionWriter;
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
     use financial_parser::model::transaction::Transaction;
     use financial_parser::model::transaction_type::TransactionType;
     use financial_parser::model::transaction_status::TransactionStatus;
@@ -27,7 +31,7 @@ mod tests {
         assert_eq!(deposit.tx_type, TransactionType::Deposit);
         assert_eq!(deposit.from_user_id, 0);
         assert_eq!(deposit.to_user_id, 501);
-        assert_eq!(deposit.amount, 50000);
+        assert_eq!(deposit.amount.minor_units, 50000);
         assert_eq!(deposit.timestamp, 1672531200000);
         assert_eq!(deposit.status, TransactionStatus::Success);
         assert_eq!(deposit.description, "Initial account funding");
@@ -47,7 +51,7 @@ mod tests {
             tx_type: TransactionType::Deposit,
             from_user_id: 0,
             to_user_id: 456,
-            amount: 100,
+            amount: Money::new(100, CurrencyCode::USD),
             timestamp: 1633036800000,
             status: TransactionStatus::Success,
             description: "Test".to_string(),
@@ -73,4 +77,140 @@ mod tests {
             assert_eq!(orig.description, parsed.description);
         }
     }
-}
\ No newline at end of file
+
+    /// Checks if incremental writer writes header exactly once
+    #[test]
+    fn test_writer_writes_header_once() {
+        let mut output = Vec::new();
+        let mut writer = YPBankCsvParser::writer(&mut output);
+        for tx_id in 1..=2 {
+            writer.write_one(&Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 456,
+                amount: Money::new(100, CurrencyCode::USD),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Success,
+                description: "Test".to_string(),
+            }).unwrap();
+        }
+        writer.finish().unwrap();
+        drop(writer);
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(output_str.matches("TX_ID").count(), 1);
+        assert_eq!(output_str.lines().count(), 3);
+        let mut empty_output = Vec::new();
+        YPBankCsvParser::writer(&mut empty_output).finish().unwrap();
+        assert_eq!(
+            String::from_utf8(empty_output).unwrap().trim_end(),
+            "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION"
+        );
+    }
+
+    /// Checks if semicolon delimited data with reordered localized headers is read with a dialect
+    #[test]
+    fn test_parse_csv_dialect() {
+        let data = indoc! {r#"
+            Описание;Сумма;TX_ID;TX_TYPE;FROM_USER_ID;TO_USER_ID;TIMESTAMP;STATUS;Валюта
+            'Payment; invoice #1';1500;1002;TRANSFER;501;502;1672534800000;SUCCESS;EUR
+        "#};
+        let dialect = CsvDialect {
+            delimiter: b';',
+            quote: b'\'',
+            header_aliases: [("Описание", "DESCRIPTION"), ("Сумма", "AMOUNT"), ("Валюта", "CURRENCY")]
+                .into_iter()
+                .map(|(alias, field)| (alias.to_string(), field.to_string()))
+                .collect(),
+            columns: None,
+        };
+        let transactions: Vec<Transaction> = YPBankCsvParser::iter_with_dialect(Cursor::new(data), dialect.clone())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].tx_id, 1002);
+        assert_eq!(transactions[0].amount, Money::new(1500, CurrencyCode::EUR));
+        assert_eq!(transactions[0].description, "Payment; invoice #1");
+
+        let error = YPBankCsvParser::iter_with_dialect(Cursor::new(data), CsvDialect { header_aliases: Default::default(), ..dialect })
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::InvalidHeader(..)));
+        assert_eq!(error.location().line, Some(1));
+    }
+
+    /// Checks if data without a header line is read and written in the dialect column order
+    #[test]
+    fn test_headerless_csv_dialect() {
+        let dialect = CsvDialect {
+            delimiter: b'\t',
+            columns: Some(
+                ["TX_ID", "AMOUNT", "TX_TYPE", "FROM_USER_ID", "TO_USER_ID", "TIMESTAMP", "STATUS", "DESCRIPTION"]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            ..CsvDialect::default()
+        };
+        let transaction = Transaction {
+            tx_id: 7,
+            tx_type: TransactionType::Withdrawal,
+            from_user_id: 501,
+            to_user_id: 0,
+            amount: Money::new(250, CurrencyCode::USD),
+            timestamp: 1633036800000,
+            status: TransactionStatus::Pending,
+            description: "ATM".to_string(),
+        };
+        let mut output = Vec::new();
+        let mut writer = YPBankCsvParser::writer_with_dialect(&mut output, dialect.clone());
+        writer.write_one(&transaction).unwrap();
+        let error = writer.write_one(&Transaction {
+            amount: Money::new(250, CurrencyCode::EUR),
+            ..transaction.clone()
+        });
+        assert!(matches!(error.unwrap_err().kind(), ParseErrorKind::Validation(_)));
+        writer.finish().unwrap();
+        drop(writer);
+        assert_eq!(String::from_utf8(output.clone()).unwrap(), "7\t250\tWITHDRAWAL\t501\t0\t1633036800000\tPENDING\tATM\n");
+        let parsed: Vec<Transaction> = YPBankCsvParser::iter_with_dialect(Cursor::new(output), dialect)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(parsed, vec![transaction]);
+    }
+
+    /// Checks if CURRENCY column is written only when chosen or needed by the first record
+    #[test]
+    fn test_writer_currency_column() {
+        let transaction = |currency| Transaction {
+            tx_id: 1,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 456,
+            amount: Money::new(100, currency),
+            timestamp: 1633036800000,
+            status: TransactionStatus::Success,
+            description: "Test".to_string(),
+        };
+        let header = |output: Vec<u8>| String::from_utf8(output).unwrap().lines().next().unwrap().to_string();
+
+        let mut output = Vec::new();
+        {
+            let mut writer = YPBankCsvParser::writer(&mut output).with_currency_column();
+            writer.write_one(&transaction(CurrencyCode::USD)).unwrap();
+            writer.finish().unwrap();
+        }
+        assert!(header(output).ends_with(",DESCRIPTION,CURRENCY"));
+
+        let mut output = Vec::new();
+        YPBankCsvParser::write(&[transaction(CurrencyCode::EUR)], &mut output).unwrap();
+        assert!(header(output.clone()).ends_with(",DESCRIPTION,CURRENCY"));
+        let parsed = YPBankCsvParser::parse(Cursor::new(output)).unwrap();
+        assert_eq!(parsed[0].amount.currency, CurrencyCode::EUR);
+
+        let mut writer = YPBankCsvParser::writer(Vec::new());
+        writer.write_one(&transaction(CurrencyCode::USD)).unwrap();
+        let error = writer.write_one(&transaction(CurrencyCode::EUR)).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::Validation(_)));
+    }
+}
@@ -1,12 +1,16 @@
 #[cfg(test)]
 mod tests {
-    use financial_parser::errors::ParseError;
+    use financial_parser::errors::ParseErrorKind;
     use std::io::Cursor;
     use indoc::indoc;
     use financial_parser::format::yp_bank_text_format::YPBankTextParser;
+    use financial_parser::format::{Format, ReadOptions, TransactionWriter};
+    use financial_parser::model::currency::CurrencyCode;
+    use financial_parser::model::money::Money;
     use financial_parser::model::transaction::Transaction;
     use financial_parser::model::transaction_type::TransactionType;
     use financial_parser::model::transaction_status::TransactionStatus;
+    use financial_parser::parser::Parser;
 
     const SAMPLE_YP_BANK_TEXT: &str = indoc! {r#"
         # Record 1 (Deposit)
@@ -59,7 +63,7 @@ mod tests {
         assert_eq!(deposit.tx_type, TransactionType::Deposit);
         assert_eq!(deposit.from_user_id, 0);
         assert_eq!(deposit.to_user_id, 9876543210987654);
-        assert_eq!(deposit.amount, 10000);
+        assert_eq!(deposit.amount.minor_units, 10000);
         assert_eq!(deposit.timestamp, 1633036800000);
         assert_eq!(deposit.status, TransactionStatus::Success);
         assert_eq!(deposit.description, "Terminal deposit");
@@ -95,9 +99,10 @@ mod tests {
         let cursor = Cursor::new(missing_field);
         let result = YPBankTextParser::parse(cursor);
         assert!(result.is_err());
-        if let Err(ParseError::Validation(msg)) = result {
-            assert!(msg.contains("Missing required field"));
-            assert!(msg.contains("DESCRIPTION"));
+        if let Err(e) = result {
+            assert!(matches!(e.kind(), ParseErrorKind::MissingField(field) if field == "DESCRIPTION"));
+            assert!(e.to_string().contains("Missing required field"));
+            assert_eq!(e.location().record_index, Some(0));
         } else {
             panic!("Expected YPBankTextParser error");
         }
@@ -119,8 +124,11 @@ mod tests {
         let cursor = Cursor::new(duplicate);
         let result = YPBankTextParser::parse(cursor);
         assert!(result.is_err());
-        if let Err(ParseError::Validation(msg)) = result {
-            assert!(msg.contains("Duplicate key"));
+        if let Err(e) = result {
+            assert!(matches!(e.kind(), ParseErrorKind::DuplicateField(field) if field == "TX_TYPE"));
+            assert!(e.to_string().contains("Duplicate key"));
+            assert_eq!(e.location().line, Some(3));
+            assert_eq!(e.location().column, Some(1));
         } else {
             panic!("Expected YPBankTextParser error");
         }
@@ -142,8 +150,12 @@ mod tests {
         let cursor = Cursor::new(no_quotes);
         let result = YPBankTextParser::parse(cursor);
         assert!(result.is_err());
-        if let Err(ParseError::Validation(msg)) = result {
-            assert!(msg.contains("must be in double quotes"));
+        if let Err(e) = result {
+            assert!(matches!(e.kind(), ParseErrorKind::UnquotedDescription(_)));
+            assert!(e.to_string().contains("must be in double quotes"));
+            assert_eq!(e.location().line, Some(8));
+            assert_eq!(e.location().column, Some(14));
+            assert_eq!(e.location().field.as_deref(), Some("DESCRIPTION"));
         } else {
             panic!("Expected YPBankTextParser error");
         }
@@ -157,15 +169,16 @@ mod tests {
             tx_type: TransactionType::Deposit,
             from_user_id: 1,
             to_user_id: 456,
-            amount: 100,
+            amount: Money::new(100, CurrencyCode::USD),
             timestamp: 1633036800000,
             status: TransactionStatus::Success,
             description: "Test".to_string(),
         };
         let result = transaction.validate();
         assert!(result.is_err());
-        if let Err(ParseError::Validation(msg)) = result {
-            assert!(msg.contains("must be 0 for DEPOSIT"));
+        if let Err(e) = result {
+            assert!(matches!(e.kind(), ParseErrorKind::InvalidUserId("FROM_USER_ID", 1, TransactionType::Deposit)));
+            assert!(e.to_string().contains("must be 0 for DEPOSIT"));
         } else {
             panic!("Expected Validation error");
         }
@@ -180,7 +193,7 @@ mod tests {
                 tx_type: TransactionType::Deposit,
                 from_user_id: 0,
                 to_user_id: 9876543210987654,
-                amount: 10000,
+                amount: Money::new(10000, CurrencyCode::USD),
                 timestamp: 1633036800000,
                 status: TransactionStatus::Success,
                 description: "Terminal deposit".to_string(),
@@ -202,4 +215,133 @@ mod tests {
             assert_eq!(orig.description, parsed.description);
         }
     }
-}
\ No newline at end of file
+
+    /// Checks if streaming reader yields records one by one and skips invalid blocks
+    #[test]
+    fn test_iter_skips_invalid_record() {
+        let data = indoc! {r#"
+            TX_ID: 1
+            TX_TYPE: DEPOSIT
+            FROM_USER_ID: 0
+            TO_USER_ID: 456
+            AMOUNT: 100
+            TIMESTAMP: 1633036800000
+            STATUS: SUCCESS
+            DESCRIPTION: "First"
+
+            TX_ID: 2
+            not a key value line
+            TX_TYPE: DEPOSIT
+
+            TX_ID: 3
+            TX_TYPE: DEPOSIT
+            FROM_USER_ID: 0
+            TO_USER_ID: 456
+            AMOUNT: 300
+            TIMESTAMP: 1633036800000
+            STATUS: SUCCESS
+            DESCRIPTION: "Third"
+        "#};
+        let mut iter = YPBankTextParser::iter(Cursor::new(data));
+        assert_eq!(iter.next().unwrap().unwrap().tx_id, 1);
+        assert!(iter.next().unwrap().is_err());
+        assert_eq!(iter.next().unwrap().unwrap().tx_id, 3);
+        assert!(iter.next().is_none());
+    }
+
+    /// Checks if incremental writer separates records with exactly one empty line
+    #[test]
+    fn test_writer_separates_records() {
+        let mut output = Vec::new();
+        let mut writer = YPBankTextParser::writer(&mut output, false);
+        for tx_id in 1..=2 {
+            writer.write_one(&Transaction {
+                tx_id,
+                tx_type: TransactionType::Deposit,
+                from_user_id: 0,
+                to_user_id: 456,
+                amount: Money::new(100, CurrencyCode::USD),
+                timestamp: 1633036800000,
+                status: TransactionStatus::Success,
+                description: "Test".to_string(),
+            }).unwrap();
+        }
+        writer.finish().unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(output_str.matches("\n\n").count(), 1);
+        assert!(!output_str.ends_with("\n\n"));
+        let transactions = YPBankTextParser::parse(Cursor::new(output_str)).unwrap();
+        assert_eq!(transactions.len(), 2);
+    }
+
+    /// Builds a text record with the given raw DESCRIPTION value
+    fn record_with_description(description: &str) -> String {
+        format!(
+            "TX_ID: 1\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 456\nAMOUNT: 100\n\
+             TIMESTAMP: 1633036800000\nSTATUS: SUCCESS\nDESCRIPTION: {}\n",
+            description
+        )
+    }
+
+    /// Checks if quotes, backslashes, newlines and control characters survive round trip
+    #[test]
+    fn test_description_escaping_round_trip() {
+        let transactions = vec![Transaction {
+            tx_id: 1,
+            tx_type: TransactionType::Deposit,
+            from_user_id: 0,
+            to_user_id: 456,
+            amount: Money::new(100, CurrencyCode::USD),
+            timestamp: 1633036800000,
+            status: TransactionStatus::Success,
+            description: "Say \"hi\"\nC:\\temp\t\u{7}".to_string(),
+        }];
+        let mut output = Vec::new();
+        YPBankTextParser::write(&transactions, &mut output, true).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains(r#"DESCRIPTION: "Say \"hi\"\nC:\\temp\u{9}\u{7}""#));
+        assert_eq!(output_str.lines().count(), 10);
+        let parsed = YPBankTextParser::iter(Cursor::new(&output_str)).with_strict_mode()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(parsed, transactions);
+    }
+
+    /// Checks if a lone double quote is reported as an error
+    #[test]
+    fn test_parse_lone_quote_description() {
+        let error = YPBankTextParser::parse(Cursor::new(record_with_description("\""))).unwrap_err();
+        assert!(matches!(error.kind(), ParseErrorKind::UnquotedDescription(_)));
+        assert_eq!(error.location().field.as_deref(), Some("DESCRIPTION"));
+    }
+
+    /// Checks if strict mode rejects ambiguous descriptions, which are kept as is by default
+    #[test]
+    fn test_strict_description() {
+        let cases = [
+            (r#""Say "hi"""#, "Say \"hi\"", 19, "unescaped quote"),
+            (r#""C:\temp""#, "C:\\temp", 17, "unknown escape \\t"),
+            (r#""\u{110000}""#, "\\u{110000}", 15, "invalid unicode escape"),
+            (r#""Trailing\""#, "Trailing\\", 23, "closing quote is escaped"),
+        ];
+        for (value, legacy, column, message) in cases {
+            let data = record_with_description(value);
+            let transactions = YPBankTextParser::parse(Cursor::new(&data)).unwrap();
+            assert_eq!(transactions[0].description, legacy);
+            let error = YPBankTextParser::iter(Cursor::new(&data)).with_strict_mode()
+                .next()
+                .unwrap()
+                .unwrap_err();
+            assert!(matches!(error.kind(), ParseErrorKind::InvalidEscape(m) if m == message));
+            assert_eq!(error.location().line, Some(8));
+            assert_eq!(error.location().column, Some(column));
+            assert_eq!(error.location().field.as_deref(), Some("DESCRIPTION"));
+            let options = ReadOptions { strict_descriptions: true, ..ReadOptions::default() };
+            let error = Parser::iter_with_options(Cursor::new(data), Format::Text, &options)
+                .next()
+                .unwrap()
+                .unwrap_err();
+            assert!(matches!(error.kind(), ParseErrorKind::InvalidEscape(m) if m == message));
+        }
+    }
+}